@@ -0,0 +1,38 @@
+extern crate ed25519_dalek as dalek;
+extern crate pbp_pkgx;
+extern crate rand;
+extern crate sha2;
+
+use dalek::Signer;
+use pbp_pkgx::sha3::Sha3_256;
+use pbp_pkgx::{HashAlgorithm, KeyFlags, PgpKey, PgpSig, SigType};
+use sha2::{Digest, Sha256, Sha512};
+
+const DATA: &[u8] = b"How will I ever get out of this labyrinth?";
+
+fn main() {
+    let keypair = dalek::SigningKey::from_bytes(&rand::random());
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "withoutboats");
+
+    // the data is hashed with SHA3-256, the key itself still uses SHA-256
+    let sig = PgpSig::new::<Sha3_256, _>(
+        DATA,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha3_256,
+        0,
+        None,
+        &[],
+        |hash| keypair.sign(hash).to_bytes(),
+    );
+
+    // parse the signature and key from their armor, as a recipient would
+    let sig = PgpSig::from_ascii_armor(&sig.to_string()).unwrap();
+    let key = PgpKey::from_ascii_armor(&key.to_string()).unwrap();
+
+    if key.verify_dalek::<Sha3_256, _>(&sig, |hasher| hasher.input(DATA)) {
+        println!("Verified successfully.");
+    } else {
+        println!("Could not verify.");
+    }
+}
@@ -6,7 +6,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use pbp_pkgx::{PgpKey, PgpSig};
-use sha2::{Sha256, Sha512};
+use sha2::{Digest, Sha256, Sha512};
 
 fn main() {
     let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
@@ -19,7 +19,8 @@ fn main() {
     let sig = PgpSig::from_ascii_armor(&sig).unwrap();
     let key = PgpKey::from_ascii_armor(&key).unwrap();
 
-    if sig.verify_dalek::<Sha256, Sha512>(data.as_bytes().into(), &key.to_dalek().unwrap()) {
+    let public_key = key.to_dalek().unwrap();
+    if sig.verify_dalek::<Sha256, Sha512, _>(&public_key, |hasher| hasher.input(data.as_bytes())) {
         println!("Verified signature.");
     } else {
         println!("Could not verify signature.");
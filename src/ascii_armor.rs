@@ -1,30 +1,42 @@
-// This module implements the ASCII armoring required by the OpenPGP
-// specification, converting binary PGP datagrams into ASCII data.
+//! ASCII armor, as required by the OpenPGP specification.
+//!
+//! Keys and signatures are armored and dearmored by their own methods,
+//! such as `PgpSig::from_ascii_armor` and `PgpKey::to_string`. This module
+//! handles text which may contain several armored blocks of any kind.
+//! `ArmorOptions` controls how `PgpSig::to_armored_string_with_options`
+//! writes armor.
 use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
 
 use byteorder::{BigEndian, ByteOrder};
 
 use crate::PgpError;
-use crate::PgpError::InvalidAsciiArmor;
+use crate::PgpError::{ChecksumMismatch, InvalidAsciiArmor};
 
 impl From<base64::DecodeError> for PgpError {
-    fn from(_: base64::DecodeError) -> PgpError {
-        InvalidAsciiArmor
+    fn from(err: base64::DecodeError) -> PgpError {
+        PgpError::InvalidBase64(err)
     }
 }
 
 // Convert from an ASCII armored string into binary data.
-pub fn remove_ascii_armor(
+//
+// If the armor has a checksum line, it must match the data. If it does
+// not, the armor is only accepted when require_checksum is false.
+pub(crate) fn remove_ascii_armor(
     s: &str,
     expected_header: &str,
     expected_footer: &str,
+    require_checksum: bool,
 ) -> Result<Vec<u8>, PgpError> {
     let lines: Vec<&str> = s.lines().map(|s| s.trim()).collect();
     let header = lines.first().ok_or(InvalidAsciiArmor)?;
     let footer = lines.last().ok_or(InvalidAsciiArmor)?;
 
     // Check header and footer
-    if !header.starts_with("-----")
+    if lines.len() < 2
+        || !header.starts_with("-----")
         || !footer.starts_with("-----")
         || !header.ends_with("-----")
         || !footer.ends_with("-----")
@@ -36,55 +48,198 @@ pub fn remove_ascii_armor(
 
     // Find the end of the header section
     let end_of_headers = 1 + lines.iter().take_while(|l| !l.is_empty()).count();
-    if end_of_headers >= lines.len() - 2 {
+    if end_of_headers >= lines.len() - 1 {
+        return Err(InvalidAsciiArmor);
+    }
+    let mut body = &lines[end_of_headers..lines.len() - 1];
+
+    // Split off the checksum line, if there is one
+    let cksum_line = match body.last() {
+        Some(line) if line.starts_with('=') && line.len() == 5 => {
+            body = &body[..body.len() - 1];
+            Some(&line[1..])
+        }
+        _ => None,
+    };
+    if body.is_empty() {
         return Err(InvalidAsciiArmor);
     }
 
     // Decode the base64'd data
-    let ascii_armored: String = lines[end_of_headers..lines.len() - 2].concat();
+    let ascii_armored: String = body.concat();
     let data = base64::decode(&ascii_armored)?;
 
     // Confirm checksum
-    let cksum_line = &lines[lines.len() - 2];
-    if !cksum_line.starts_with("=") || !cksum_line.len() > 1 {
-        return Err(InvalidAsciiArmor);
-    }
-    let mut cksum = [0; 4];
-    base64::decode_config_slice(&cksum_line[1..], base64::STANDARD, &mut cksum[..])?;
-    if BigEndian::read_u32(&cksum[..]) != checksum_crc24(&data) {
-        return Err(InvalidAsciiArmor);
+    match cksum_line {
+        Some(cksum_line) => {
+            let mut cksum = [0; 4];
+            base64::decode_config_slice(cksum_line, base64::STANDARD, &mut cksum[1..])?;
+            if BigEndian::read_u32(&cksum[..]) != checksum_crc24(&data) {
+                return Err(ChecksumMismatch);
+            }
+        }
+        None if require_checksum => return Err(InvalidAsciiArmor),
+        None => {}
     }
 
     Ok(data)
 }
 
-// Ascii armors data into the formatter
-pub fn ascii_armor(
+/// Find and decode every armored block in the text.
+///
+/// Each block is returned with its label, the text between `BEGIN` and
+/// the dashes of its first line, such as `PGP SIGNATURE` or
+/// `PGP PUBLIC KEY BLOCK`. The label tells the caller whether to parse the
+/// data with `PgpSig::from_bytes`, `PgpKey::from_bytes` or otherwise.
+///
+/// Text outside of the blocks is ignored. Blocks which cannot be decoded
+/// are skipped: those whose `END` line has a different label, which are
+/// not ended before another block begins, or whose contents are not valid
+/// armor. A block without a checksum line is accepted.
+pub fn armored_blocks(input: &str) -> impl Iterator<Item = (String, Vec<u8>)> + '_ {
+    let mut lines = input.lines().map(str::trim);
+    let mut block: Option<(&str, Vec<&str>)> = None;
+    core::iter::from_fn(move || loop {
+        let line = lines.next()?;
+        if armor_line_label(line, "BEGIN ").is_some() {
+            block = Some((line, vec![line]));
+            continue;
+        }
+        let Some((begin, block_lines)) = block.as_mut() else {
+            continue;
+        };
+        block_lines.push(line);
+        let Some(end) = armor_line_label(line, "END ") else {
+            continue;
+        };
+        let begin = armor_line_label(begin, "BEGIN ").unwrap_or_default();
+        let text = block_lines.join("\n");
+        block = None;
+        if end != begin {
+            continue;
+        }
+        let header = ["BEGIN ", begin].concat();
+        let footer = ["END ", end].concat();
+        if let Ok(data) = remove_ascii_armor(&text, &header, &footer, false) {
+            return Some((begin.to_string(), data));
+        }
+    })
+}
+
+/// The label of the first armored block in the text, if there is one.
+///
+/// This is the text between `BEGIN` and the dashes of the block's first
+/// line, such as `PGP SIGNATURE` or `PGP PUBLIC KEY BLOCK`. The block is not
+/// decoded, so this lets a caller which expected one kind of block explain
+/// that it was given another, rather than reporting only that the armor is
+/// invalid.
+pub fn peek_armor_label(input: &str) -> Option<&str> {
+    input
+        .lines()
+        .find_map(|line| armor_line_label(line.trim(), "BEGIN "))
+}
+
+// The label of a BEGIN or END armor line, such as "PGP SIGNATURE".
+fn armor_line_label<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.strip_prefix("-----")?
+        .strip_suffix("-----")?
+        .strip_prefix(kind)
+}
+
+/// Options for writing ASCII armor.
+///
+/// The default options write no armor headers, wrap the base64 data at 64
+/// characters, as GnuPG does, and write a checksum line:
+///
+/// ```
+/// use pbp_pkgx::ascii_armor::ArmorOptions;
+///
+/// let options = ArmorOptions {
+///     checksum: false,
+///     ..ArmorOptions::default()
+/// };
+/// assert_eq!(options.line_width, 64);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ArmorOptions<'a> {
+    /// Armor header lines, each written as `key: value`, such as
+    /// `("Comment", "signed by pkgx")`.
+    pub headers: &'a [(&'a str, &'a str)],
+    /// The number of characters at which to wrap the base64 data. This
+    /// must not be zero.
+    pub line_width: usize,
+    /// Whether to write the CRC24 checksum line. RFC 4880 requires it,
+    /// but RFC 9580 deprecates it, and recommends omitting it.
+    pub checksum: bool,
+}
+
+impl<'a> Default for ArmorOptions<'a> {
+    fn default() -> ArmorOptions<'a> {
+        ArmorOptions {
+            headers: &[],
+            line_width: 64,
+            checksum: true,
+        }
+    }
+}
+
+// Ascii armors data into the formatter, with the given armor headers and
+// the base64 data wrapped at 64 characters, as GnuPG does
+pub(crate) fn ascii_armor<W: fmt::Write>(
     header: &'static str,
     footer: &'static str,
+    armor_headers: &[(&str, &str)],
     data: &[u8],
-    f: &mut fmt::Formatter,
+    f: &mut W,
 ) -> fmt::Result {
+    let options = ArmorOptions {
+        headers: armor_headers,
+        ..ArmorOptions::default()
+    };
+    ascii_armor_with_options(header, footer, data, &options, f)
+}
+
+// Ascii armors data into the formatter, as the options describe
+pub(crate) fn ascii_armor_with_options<W: fmt::Write>(
+    header: &'static str,
+    footer: &'static str,
+    data: &[u8],
+    options: &ArmorOptions,
+    f: &mut W,
+) -> fmt::Result {
+    assert!(options.line_width > 0, "armor line width must not be zero");
+
     // Header Line
     f.write_str("-----")?;
     f.write_str(header)?;
-    f.write_str("-----\n\n")?;
+    f.write_str("-----\n")?;
+
+    // Armor Headers
+    for (key, value) in options.headers {
+        f.write_str(key)?;
+        f.write_str(": ")?;
+        f.write_str(value)?;
+        f.write_str("\n")?;
+    }
+    f.write_str("\n")?;
 
     // Base64'd data
     let b64_cfg = base64::Config::new(
         base64::CharacterSet::Standard,
         true,
         false,
-        base64::LineWrap::Wrap(76, base64::LineEnding::LF),
+        base64::LineWrap::Wrap(options.line_width, base64::LineEnding::LF),
     );
     f.write_str(&base64::encode_config(data, b64_cfg))?;
-    f.write_str("\n=")?;
 
     // Checksum
-    let cksum = checksum_crc24(data);
-    let mut cksum_buf = [0; 4];
-    BigEndian::write_u32(&mut cksum_buf, cksum);
-    f.write_str(&base64::encode(&cksum_buf[1..4]))?;
+    if options.checksum {
+        let cksum = checksum_crc24(data);
+        let mut cksum_buf = [0; 4];
+        BigEndian::write_u32(&mut cksum_buf, cksum);
+        f.write_str("\n=")?;
+        f.write_str(&base64::encode(&cksum_buf[1..4]))?;
+    }
 
     // Footer Line
     f.write_str("\n-----")?;
@@ -0,0 +1,66 @@
+//! Ready-made ed25519 verification for `verify` functions.
+//!
+//! `PgpSig::verify` and `PgpKey::verify` take a function which checks an
+//! ed25519 signature, so that this library does not depend on any
+//! particular ed25519 implementation. The types in this module implement
+//! `VerifyBackend` with the implementation of the same name, each behind a
+//! feature: `Dalek` with `dalek`, `Ring` with `ring`, and `Ed25519Compact`
+//! with `ed25519-compact`. Pass one to `PgpSig::verify_with` or
+//! `PgpKey::verify_with`, or pass `B::verify` as the verify function to
+//! the other methods which take one.
+use crate::Signature;
+
+/// An implementation of ed25519 signature verification.
+pub trait VerifyBackend {
+    /// Check an ed25519 signature of the data by the public key.
+    ///
+    /// Returns false if the public key is not a valid ed25519 public key.
+    fn verify(public_key: &[u8; 32], data: &[u8], signature: Signature) -> bool;
+}
+
+#[cfg(feature = "dalek")]
+/// Verification with ed25519-dalek.
+///
+/// Signatures are checked with `verify_strict`, as in `PgpSig::verify_dalek`,
+/// which rejects weak public keys and non-canonical signatures.
+#[derive(Copy, Clone, Debug)]
+pub struct Dalek;
+
+#[cfg(feature = "dalek")]
+impl VerifyBackend for Dalek {
+    fn verify(public_key: &[u8; 32], data: &[u8], signature: Signature) -> bool {
+        let key = match dalek::VerifyingKey::from_bytes(public_key) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        let sig = dalek::Signature::from_bytes(&signature);
+        key.verify_strict(data, &sig).is_ok()
+    }
+}
+
+#[cfg(feature = "ring")]
+/// Verification with ring.
+#[derive(Copy, Clone, Debug)]
+pub struct Ring;
+
+#[cfg(feature = "ring")]
+impl VerifyBackend for Ring {
+    fn verify(public_key: &[u8; 32], data: &[u8], signature: Signature) -> bool {
+        let key = ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key);
+        key.verify(data, &signature).is_ok()
+    }
+}
+
+#[cfg(feature = "ed25519-compact")]
+/// Verification with ed25519-compact.
+#[derive(Copy, Clone, Debug)]
+pub struct Ed25519Compact;
+
+#[cfg(feature = "ed25519-compact")]
+impl VerifyBackend for Ed25519Compact {
+    fn verify(public_key: &[u8; 32], data: &[u8], signature: Signature) -> bool {
+        let key = ed25519_compact::PublicKey::new(*public_key);
+        let sig = ed25519_compact::Signature::new(signature);
+        key.verify(data, &sig).is_ok()
+    }
+}
@@ -0,0 +1,113 @@
+use alloc::vec::Vec;
+
+use digest::Digest;
+
+use crate::sig::hash_canonical_text;
+use crate::{Fingerprint, HashAlgorithm, PgpSig, SigType, Signature, SubPacket};
+
+/// A builder for PGP signatures.
+///
+/// This is an alternative to `PgpSig::new` which names each option rather
+/// than taking them all as positional arguments. It starts out as a binary
+/// document signature using SHA-256 with a timestamp of 0 and no
+/// expiration or extra subpackets; in most cases the timestamp should be
+/// set.
+#[derive(Clone, Debug)]
+pub struct SigBuilder<'a> {
+    sig_type: SigType,
+    hash_algorithm: HashAlgorithm,
+    unix_time: u32,
+    expiration: Option<u32>,
+    subpackets: Vec<SubPacket<'a>>,
+}
+
+impl<'a> SigBuilder<'a> {
+    /// Construct a builder with the default options.
+    pub fn new() -> SigBuilder<'a> {
+        SigBuilder {
+            sig_type: SigType::BinaryDocument,
+            hash_algorithm: HashAlgorithm::Sha256,
+            unix_time: 0,
+            expiration: None,
+            subpackets: vec![],
+        }
+    }
+
+    /// Set the signature type.
+    ///
+    /// For `TextDocument` signatures the data is canonicalized before it
+    /// is hashed, as in `PgpSig::new_text`.
+    pub fn sig_type(mut self, sig_type: SigType) -> SigBuilder<'a> {
+        self.sig_type = sig_type;
+        self
+    }
+
+    /// Set the hash algorithm.
+    ///
+    /// This must match the digest passed to `sign`.
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> SigBuilder<'a> {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    /// Set the signature creation time, as a unix timestamp.
+    pub fn timestamp(mut self, unix_time: u32) -> SigBuilder<'a> {
+        self.unix_time = unix_time;
+        self
+    }
+
+    /// Set the number of seconds after the creation time at which the
+    /// signature expires.
+    pub fn expiration(mut self, expiration: u32) -> SigBuilder<'a> {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Add a hashed subpacket.
+    ///
+    /// Subpackets are written in the order they are added, after the
+    /// fingerprint, timestamp and expiration subpackets.
+    pub fn subpacket(mut self, subpacket: SubPacket<'a>) -> SigBuilder<'a> {
+        self.subpackets.push(subpacket);
+        self
+    }
+
+    /// Sign the data, producing the signature.
+    ///
+    /// The fingerprint and sign function are the same as for
+    /// `PgpSig::new`.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of the hash algorithm.
+    pub fn sign<D, F>(&self, data: &[u8], fingerprint: Fingerprint, sign: F) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let text = self.sig_type == SigType::TextDocument;
+        PgpSig::new_with_input::<D, _, _>(
+            |hasher| {
+                if text {
+                    hash_canonical_text(hasher, data)
+                } else {
+                    hasher.process(data)
+                }
+            },
+            fingerprint,
+            self.sig_type,
+            self.hash_algorithm,
+            self.unix_time,
+            self.expiration,
+            &self.subpackets,
+            sign,
+        )
+    }
+}
+
+impl<'a> Default for SigBuilder<'a> {
+    fn default() -> SigBuilder<'a> {
+        SigBuilder::new()
+    }
+}
@@ -0,0 +1,168 @@
+//! The OpenPGP cleartext signature framework.
+//!
+//! A cleartext signed document contains the signed text itself, readable
+//! without any OpenPGP software, followed by an ASCII armored text document
+//! signature:
+//!
+//! ```text
+//! -----BEGIN PGP SIGNED MESSAGE-----
+//! Hash: SHA256
+//!
+//! The signed text, with lines beginning with a dash escaped.
+//! -----BEGIN PGP SIGNATURE-----
+//! ...
+//! -----END PGP SIGNATURE-----
+//! ```
+//!
+//! The signed text is canonicalized before hashing: trailing spaces and
+//! tabs are removed from each line, and lines are joined with CRLF. The
+//! line ending before the signature is not part of the signed text, so
+//! `sign` writes one after the text, and `parse` returns the text as it was
+//! signed, including any line endings at its end.
+use std::string::{String, ToString};
+
+use digest::Digest;
+
+use crate::sig::hash_canonical_text;
+use crate::PgpError;
+use crate::{Fingerprint, HashAlgorithm, PgpSig, SigType, Signature};
+
+const HEADER: &str = "-----BEGIN PGP SIGNED MESSAGE-----";
+const SIG_HEADER: &str = "-----BEGIN PGP SIGNATURE-----";
+const SIG_FOOTER: &str = "-----END PGP SIGNATURE-----";
+
+/// Sign this text, producing a cleartext signed document.
+///
+/// The text is hashed using the digest `D`, which must implement the given
+/// hash algorithm. The sign function must be a valid function for signing
+/// data with the private key paired with the fingerprint, as in
+/// `PgpSig::new`.
+pub fn sign<D, F>(
+    text: &str,
+    fingerprint: Fingerprint,
+    hash_algorithm: HashAlgorithm,
+    unix_time: u32,
+    sign: F,
+) -> String
+where
+    D: Digest,
+    F: FnOnce(&[u8]) -> Signature,
+{
+    let sig = PgpSig::new_with_input::<D, _, _>(
+        |hasher| hash_canonical_text(hasher, text.as_bytes()),
+        fingerprint,
+        SigType::TextDocument,
+        hash_algorithm,
+        unix_time,
+        None,
+        &[],
+        sign,
+    );
+
+    let mut document = String::with_capacity(text.len() + 400);
+    document.push_str(HEADER);
+    document.push_str("\nHash: ");
+    document.push_str(hash_name(hash_algorithm));
+    document.push_str("\n\n");
+    // split into lines as hash_canonical_text does, keeping a last empty
+    // line, so that parse returns the text which was hashed
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    for line in text.split('\n') {
+        if line.starts_with('-') {
+            document.push_str("- ");
+        }
+        document.push_str(line);
+        document.push('\n');
+    }
+    document.push_str(&sig.to_string());
+    document
+}
+
+/// Split a cleartext signed document into its text and its signature.
+///
+/// Dash escaping is removed from the returned text, and its lines are
+/// joined with LF. If the document has `Hash` headers, they must include
+/// the signature's hash algorithm.
+pub fn parse(document: &str) -> Result<(String, PgpSig), PgpError> {
+    let mut lines = document
+        .lines()
+        .map(|line| line.trim_end())
+        .skip_while(|line| line.is_empty());
+
+    if lines.next() != Some(HEADER) {
+        return Err(PgpError::InvalidCleartext);
+    }
+
+    // Armor headers, up to the first blank line
+    let mut hash_names = vec![];
+    for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+        match line.strip_prefix("Hash:") {
+            Some(names) => hash_names.extend(names.split(',').map(|name| name.trim())),
+            None => return Err(PgpError::InvalidCleartext),
+        }
+    }
+
+    // Dash escaped text, up to the signature
+    let mut text = vec![];
+    loop {
+        let line = lines.next().ok_or(PgpError::InvalidCleartext)?;
+        if line == SIG_HEADER {
+            break;
+        }
+        text.push(match line.strip_prefix('-') {
+            Some(escaped) => escaped
+                .strip_prefix(' ')
+                .ok_or(PgpError::InvalidCleartext)?,
+            None => line,
+        });
+    }
+    let text = text.join("\n");
+
+    // Signature
+    let mut armored = String::from(SIG_HEADER);
+    armored.push('\n');
+    for line in lines {
+        armored.push_str(line);
+        armored.push('\n');
+        if line == SIG_FOOTER {
+            break;
+        }
+    }
+    let sig = PgpSig::from_ascii_armor(&armored)?;
+
+    if !hash_names.is_empty() && !hash_names.contains(&hash_name(sig.hash_algorithm())) {
+        return Err(PgpError::InvalidCleartext);
+    }
+
+    Ok((text, sig))
+}
+
+/// Verify text from a cleartext signed document against its signature.
+///
+/// The text is canonicalized and hashed using the digest `D`, and the
+/// verify function is called as in `PgpSig::verify`.
+pub fn verify<D, F>(text: &str, sig: &PgpSig, verify: F) -> bool
+where
+    D: Digest,
+    F: FnOnce(&[u8], Signature) -> bool,
+{
+    sig.try_sig_type().ok() == Some(SigType::TextDocument)
+        && sig.verify::<D, _, _>(
+            |hasher| hash_canonical_text(hasher, text.as_bytes()),
+            verify,
+        )
+}
+
+fn hash_name(hash_algorithm: HashAlgorithm) -> &'static str {
+    match hash_algorithm {
+        HashAlgorithm::Md5 => "MD5",
+        HashAlgorithm::Sha1 => "SHA1",
+        HashAlgorithm::Ripemd160 => "RIPEMD160",
+        HashAlgorithm::Sha256 => "SHA256",
+        HashAlgorithm::Sha384 => "SHA384",
+        HashAlgorithm::Sha512 => "SHA512",
+        HashAlgorithm::Sha224 => "SHA224",
+        HashAlgorithm::Sha3_256 => "SHA3-256",
+        HashAlgorithm::Sha3_512 => "SHA3-512",
+    }
+}
@@ -0,0 +1,104 @@
+//! Helpers for making and checking detached signatures of files.
+//!
+//! Files are streamed through the hasher in chunks, so signing or verifying
+//! a large file does not read it into memory.
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use digest::Digest;
+
+use crate::PgpError;
+use crate::{Fingerprint, HashAlgorithm, PgpSig, SigType, Signature};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl From<io::Error> for PgpError {
+    fn from(err: io::Error) -> PgpError {
+        PgpError::Io(err)
+    }
+}
+
+/// Make a detached binary document signature of the file at this path.
+///
+/// The file is hashed using the digest `D`, which must implement the given
+/// hash algorithm. The sign function must be a valid function for signing
+/// data with the private key paired with the fingerprint, as in
+/// `PgpSig::new`.
+///
+/// The whole file is hashed before the sign function is called, so if it
+/// cannot be read, the error is returned and nothing is signed.
+///
+/// The signature is returned rather than written anywhere; conventionally
+/// its ASCII armored form (its Display output) is written to a file of the
+/// same name with an `.asc` extension appended.
+pub fn sign_file<D, F, P>(
+    path: P,
+    fingerprint: Fingerprint,
+    hash_algorithm: HashAlgorithm,
+    unix_time: u32,
+    sign: F,
+) -> Result<PgpSig, PgpError>
+where
+    D: Digest,
+    F: FnOnce(&[u8]) -> Signature,
+    P: AsRef<Path>,
+{
+    let mut file = File::open(path)?;
+    let mut file_hasher = D::default();
+    hash_reader(&mut file, &mut file_hasher)?;
+    Ok(PgpSig::from_prehashed::<D, _, _>(
+        |hasher| *hasher = file_hasher,
+        fingerprint,
+        SigType::BinaryDocument,
+        hash_algorithm,
+        unix_time,
+        sign,
+    ))
+}
+
+/// Verify the file at `data_path` against the detached signature at
+/// `sig_path`.
+///
+/// The signature may be ASCII armored or binary. The file is hashed using
+/// the digest `D`, and the verify function is called as in
+/// `PgpSig::verify`. An error is returned only if a file cannot be read or
+/// the signature cannot be parsed; a signature which does not verify
+/// returns `Ok(false)`.
+pub fn verify_file<D, F, P1, P2>(data_path: P1, sig_path: P2, verify: F) -> Result<bool, PgpError>
+where
+    D: Digest,
+    F: FnOnce(&[u8], Signature) -> bool,
+    P1: AsRef<Path>,
+    P2: AsRef<Path>,
+{
+    let sig_data = fs::read(sig_path)?;
+    let sig = if sig_data.starts_with(b"-----") {
+        let armored = std::str::from_utf8(&sig_data).map_err(|_| PgpError::InvalidAsciiArmor)?;
+        PgpSig::from_ascii_armor(armored)?
+    } else {
+        PgpSig::from_bytes(&sig_data)?
+    };
+
+    let mut file = File::open(data_path)?;
+    let mut result = Ok(());
+    let verified = sig.verify::<D, _, _>(|hasher| result = hash_reader(&mut file, hasher), verify);
+    result?;
+    Ok(verified)
+}
+
+fn hash_reader<D: Digest, R: Read>(reader: &mut R, hasher: &mut D) -> Result<(), PgpError> {
+    // the buffer holds the contents of the file, which may be sensitive
+    #[cfg(feature = "zeroize")]
+    let mut buf = zeroize::Zeroizing::new(vec![0; CHUNK_SIZE]);
+    #[cfg(not(feature = "zeroize"))]
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => hasher.process(&buf[..n]),
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
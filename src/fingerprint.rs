@@ -0,0 +1,147 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display, LowerHex, UpperHex};
+use core::ops::Deref;
+use core::str::FromStr;
+
+#[cfg(feature = "subtle")]
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::PgpError;
+
+/// An OpenPGP public key fingerprint.
+///
+/// This type implements Display the way GnuPG prints fingerprints: forty
+/// uppercase hex digits in groups of four, with an extra space between
+/// the two halves. It can be parsed from that format, or from forty hex
+/// digits with no spaces.
+///
+/// With the `subtle` feature, fingerprints implement `ConstantTimeEq`.
+/// Code deciding whether an untrusted fingerprint matches a trusted one
+/// should compare them with `ct_eq` rather than `==`, so the comparison
+/// does not reveal how many leading bytes matched.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
+pub struct Fingerprint(pub [u8; 20]);
+
+impl Fingerprint {
+    /// The all-zero fingerprint.
+    ///
+    /// No real key has this fingerprint, so it can stand for a fingerprint
+    /// which could not be determined. Signatures with an all-zero
+    /// fingerprint subpacket are rejected when parsed.
+    pub const ZERO: Fingerprint = Fingerprint([0; 20]);
+
+    /// Whether this is the all-zero fingerprint, `Fingerprint::ZERO`.
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&byte| byte == 0)
+    }
+}
+
+impl Deref for Fingerprint {
+    type Target = [u8; 20];
+
+    fn deref(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Fingerprint {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl From<[u8; 20]> for Fingerprint {
+    fn from(bytes: [u8; 20]) -> Fingerprint {
+        Fingerprint(bytes)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl ConstantTimeEq for Fingerprint {
+    fn ct_eq(&self, other: &Fingerprint) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl LowerHex for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl UpperHex for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, group) in self.0.chunks(2).enumerate() {
+            match i {
+                0 => {}
+                5 => f.write_str("  ")?,
+                _ => f.write_str(" ")?,
+            }
+            write!(f, "{:02X}{:02X}", group[0], group[1])?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = PgpError;
+    fn from_str(s: &str) -> Result<Fingerprint, PgpError> {
+        let digits: Vec<u8> = s
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| match b {
+                b'0'..=b'9' => Ok(b - b'0'),
+                b'a'..=b'f' => Ok(b - b'a' + 10),
+                b'A'..=b'F' => Ok(b - b'A' + 10),
+                _ => Err(PgpError::InvalidFingerprint),
+            })
+            .collect::<Result<_, _>>()?;
+
+        if digits.len() != 40 {
+            return Err(PgpError::InvalidFingerprint);
+        }
+
+        let mut fingerprint = [0; 20];
+        for (byte, pair) in fingerprint.iter_mut().zip(digits.chunks(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+        Ok(Fingerprint(fingerprint))
+    }
+}
+
+/// An OpenPGP key ID: the low 64 bits of a version 4 key fingerprint.
+///
+/// This type implements Display as sixteen uppercase hex digits, the way
+/// GnuPG prints key IDs.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
+pub struct KeyId(pub [u8; 8]);
+
+impl KeyId {
+    /// Get the key ID of the key with this fingerprint.
+    pub fn from_fingerprint(fingerprint: &Fingerprint) -> KeyId {
+        let mut key_id = [0; 8];
+        key_id.copy_from_slice(&fingerprint[12..20]);
+        KeyId(key_id)
+    }
+}
+
+impl Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
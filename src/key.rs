@@ -1,6 +1,10 @@
-use std::fmt::{self, Debug, Display};
-use std::ops::Range;
-use std::str::FromStr;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use core::{fmt::Display, str::FromStr};
 
 use byteorder::{BigEndian, ByteOrder};
 use digest::Digest;
@@ -11,20 +15,66 @@ use typenum::U32;
 use dalek::Signer;
 #[cfg(feature = "dalek")]
 use ed25519_dalek as dalek;
+#[cfg(feature = "subtle")]
+use subtle::ConstantTimeEq;
 #[cfg(feature = "dalek")]
 use typenum::U64;
 
+#[cfg(feature = "std")]
 use crate::ascii_armor::{ascii_armor, remove_ascii_armor};
+#[cfg(feature = "dalek")]
+use crate::backend::Dalek;
 use crate::packet::*;
 use crate::Base64;
 
+use crate::sig::SubPacketIter;
 use crate::PgpError;
-use crate::{Fingerprint, KeyFlags, Signature};
-use crate::{PgpSig, SigType, SubPacket};
+use crate::{Fingerprint, KeyFlags, Signature, VerifyBackend};
+use crate::{HashAlgorithm, PgpSig, RevocationReason, SigType, SubPacket, Subkey};
 
 // curve identifier (curve25519)
 const CURVE: &[u8] = &[0x09, 0x2b, 0x06, 0x01, 0x04, 0x01, 0xda, 0x47, 0x0f, 0x01];
 
+/// The algorithm and key server preferences advertised in a key's
+/// self-signature.
+///
+/// Other OpenPGP implementations consult these when signing or encrypting
+/// to the key. Each list is in order of preference, and an empty list is
+/// left out of the self-signature. Symmetric and compression algorithms
+/// are given as their RFC 4880 algorithm IDs.
+///
+/// The default prefers SHA-512, then SHA-256, states no symmetric or
+/// compression preferences and no preferred key server, and asks key
+/// servers not to let anyone but the key's owner modify it.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Preferences {
+    /// The preferred hash algorithms (subpacket 21).
+    pub hash_algorithms: Vec<HashAlgorithm>,
+    /// The preferred symmetric algorithms (subpacket 11).
+    pub symmetric_algorithms: Vec<u8>,
+    /// The preferred compression algorithms (subpacket 22).
+    pub compression_algorithms: Vec<u8>,
+    /// The URI of the key server from which updates to the key should be
+    /// fetched (subpacket 24).
+    pub key_server: Option<String>,
+    /// Whether to set the no-modify flag of the key server preferences
+    /// (subpacket 23), which asks key servers to accept changes to the
+    /// key only from its owner.
+    pub key_server_no_modify: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Preferences {
+        Preferences {
+            hash_algorithms: vec![HashAlgorithm::Sha512, HashAlgorithm::Sha256],
+            symmetric_algorithms: vec![],
+            compression_algorithms: vec![],
+            key_server: None,
+            key_server_no_modify: true,
+        }
+    }
+}
+
 /// An OpenPGP formatted ed25519 public key.
 ///
 /// This allows you to transmit an ed25519 key as a PGP key. Though gpg
@@ -34,9 +84,13 @@ const CURVE: &[u8] = &[0x09, 0x2b, 0x06, 0x01, 0x04, 0x01, 0xda, 0x47, 0x0f, 0x0
 /// formatted key is expected.
 ///
 /// This type implements Display by ASCII armoring the public key data.
-#[derive(Eq, PartialEq, Hash)]
+/// Keys are ordered primarily by their binary representation.
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct PgpKey {
     data: Vec<u8>,
+    user_ids: Vec<String>,
+    subkeys: Vec<Subkey>,
+    revocation: Option<PgpSig>,
 }
 
 impl PgpKey {
@@ -48,7 +102,12 @@ impl PgpKey {
     /// - A public key packet (formatted according to the "EdDSA for OpenPGP"
     ///   extension draft)
     /// - A user id (whatever string you pass as the user id argument)
-    /// - A self-signature
+    /// - A positive certification self-signature, with a key flags
+    ///   subpacket for the flags argument and the default `Preferences`
+    ///
+    /// OpenPGP implementations use the key flags to decide what the key may
+    /// be used for; a key with `KeyFlags::NONE` will not be considered
+    /// usable for signing. The flags can be read back with `key_flags`.
     ///
     /// The sign function must be a valid function for signing data with the
     /// private key paired with the public key. You are required to provide
@@ -70,43 +129,78 @@ impl PgpKey {
         Sha256: Digest<OutputSize = U32>,
         F: Fn(&[u8]) -> Signature,
     {
-        assert!(key.len() == 32);
+        PgpKey::new_with_expiration::<Sha256, _>(key, flags, user_id, unix_time, None, sign)
+    }
 
-        let mut data = Vec::with_capacity(user_id.len() + 180);
+    /// Construct a PgpKey from an ed25519 public key, which expires.
+    ///
+    /// This is the same as `new`, except that if an expiration is given,
+    /// the self-signature contains a key expiration time subpacket. The
+    /// expiration is the number of seconds after the key's creation time
+    /// at which it expires.
+    pub fn new_with_expiration<Sha256, F>(
+        key: &[u8],
+        flags: KeyFlags,
+        user_id: &str,
+        unix_time: u32,
+        expiration: Option<u32>,
+        sign: F,
+    ) -> PgpKey
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        PgpKey::new_with_preferences::<Sha256, _>(
+            key,
+            flags,
+            user_id,
+            unix_time,
+            expiration,
+            &Preferences::default(),
+            sign,
+        )
+    }
 
-        let key_packet_range = write_public_key_packet(&mut data, key, unix_time);
-        let fingerprint = fingerprint(&data[key_packet_range.clone()]);
-        write_user_id_packet(&mut data, user_id);
+    /// Construct a PgpKey from an ed25519 public key, with the given
+    /// algorithm preferences.
+    ///
+    /// This is the same as `new_with_expiration`, except that the
+    /// self-signature advertises `preferences` rather than the defaults.
+    pub fn new_with_preferences<Sha256, F>(
+        key: &[u8],
+        flags: KeyFlags,
+        user_id: &str,
+        unix_time: u32,
+        expiration: Option<u32>,
+        preferences: &Preferences,
+        sign: F,
+    ) -> PgpKey
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        assert!(key.len() == 32);
 
-        let sig_data = {
-            let mut data = Vec::from(&data[key_packet_range]);
-            data.extend(&[0xb4]);
-            data.extend(&bigendian_u32(user_id.len() as u32));
-            data.extend(user_id.as_bytes());
-            data
-        };
+        let mut data = Vec::with_capacity(user_id.len() + 180);
 
-        let signature_packet = PgpSig::new::<Sha256, _>(
-            &sig_data,
-            fingerprint,
-            SigType::PositiveCertification,
+        write_public_key_packet(&mut data, 6, key, unix_time);
+        write_certification::<Sha256, _>(
+            &mut data,
+            user_id.as_bytes(),
+            false,
+            flags,
             unix_time,
-            &[
-                SubPacket {
-                    tag: 27,
-                    data: &[flags.bits()],
-                },
-                SubPacket {
-                    tag: 23,
-                    data: &[0x80],
-                },
-            ],
+            expiration,
+            preferences,
             sign,
         );
 
-        data.extend(signature_packet.as_bytes());
-
-        PgpKey { data }
+        PgpKey {
+            data,
+            user_ids: vec![user_id.to_owned()],
+            subkeys: vec![],
+            revocation: None,
+        }
     }
 
     /// Construct a PgpKey struct from an OpenPGP public key.
@@ -119,6 +213,10 @@ impl PgpKey {
     ///
     /// As a result, a key constructed this way many not successfully import
     /// into an OpenPGP implementation like gpg.
+    ///
+    /// Key revocation signatures in the data are not checked either, so
+    /// the key is not revoked by them until `add_embedded_revocation` is
+    /// called.
     pub fn from_bytes(bytes: &[u8]) -> Result<PgpKey, PgpError> {
         let (packet_data, end) = find_public_key_packet(bytes)?;
 
@@ -139,15 +237,41 @@ impl PgpKey {
             bytes.to_owned()
         };
 
-        Ok(PgpKey { data })
+        let user_ids = read_user_ids(&data)?;
+        let subkeys = read_subkeys(&data);
+
+        Ok(PgpKey {
+            data,
+            user_ids,
+            subkeys,
+            revocation: None,
+        })
     }
 
+    #[cfg(feature = "std")]
     /// Construct a PgpKey from an ASCII armored string.
+    ///
+    /// If the armor has a checksum line, it must match the data, but armor
+    /// without a checksum line is accepted.
     pub fn from_ascii_armor(string: &str) -> Result<PgpKey, PgpError> {
         let data = remove_ascii_armor(
             string,
             "BEGIN PGP PUBLIC KEY BLOCK",
             "END PGP PUBLIC KEY BLOCK",
+            false,
+        )?;
+        PgpKey::from_bytes(&data)
+    }
+
+    #[cfg(feature = "std")]
+    /// Construct a PgpKey from an ASCII armored string, requiring the armor
+    /// to have a valid checksum line.
+    pub fn from_ascii_armor_strict(string: &str) -> Result<PgpKey, PgpError> {
+        let data = remove_ascii_armor(
+            string,
+            "BEGIN PGP PUBLIC KEY BLOCK",
+            "END PGP PUBLIC KEY BLOCK",
+            true,
         )?;
         PgpKey::from_bytes(&data)
     }
@@ -156,9 +280,413 @@ impl PgpKey {
     ///
     /// This slice will be thirty-two bytes long.
     pub fn key_data(&self) -> [u8; 32] {
-        let mut rv = [0; 32];
-        rv.copy_from_slice(&self.data[22..54]);
-        rv
+        self.public_key_bytes()
+    }
+
+    /// The ed25519 public key contained in this key, as a compressed
+    /// Edwards point.
+    ///
+    /// For an EdDSA key, this is read from the key's MPI, without the MPI's
+    /// bit count or the `0x40` prefix EdDSA uses to mark a native point
+    /// encoding.
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        ed25519_key(&self.key_packet()[3..])
+    }
+
+    /// Build a transferable public key from this key.
+    ///
+    /// This contains the public key packet, followed by the first user id
+    /// of this key and a new positive certification self-signature over
+    /// them, made at the key's creation time with the key's current flags,
+    /// expiration and algorithm preferences.
+    /// Any other packets in this key are not included.
+    ///
+    /// The sign function must sign data with the private key paired with
+    /// this public key, as in `new`. This returns an error if this key has
+    /// no user id.
+    pub fn to_transferable<Sha256, F>(&self, sign: F) -> Result<Vec<u8>, PgpError>
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let user_id = self.user_ids.first().ok_or(PgpError::MissingUserId)?;
+
+        let mut data = self.key_packet().to_owned();
+        write_certification::<Sha256, _>(
+            &mut data,
+            user_id.as_bytes(),
+            false,
+            self.key_flags(),
+            self.created_at(),
+            self.key_expiration(),
+            &self.current_preferences(),
+            sign,
+        );
+        Ok(data)
+    }
+
+    #[cfg(feature = "std")]
+    /// Build an ASCII armored transferable public key from this key.
+    ///
+    /// See `to_transferable`.
+    pub fn to_transferable_armored<Sha256, F>(&self, sign: F) -> Result<String, PgpError>
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let data = self.to_transferable::<Sha256, _>(sign)?;
+        let mut string = String::new();
+        ascii_armor(
+            "BEGIN PGP PUBLIC KEY BLOCK",
+            "END PGP PUBLIC KEY BLOCK",
+            &[],
+            &data,
+            &mut string,
+        )
+        .expect("writing to a String cannot fail");
+        Ok(string)
+    }
+
+    /// The user ids of this key, in the order they appear.
+    ///
+    /// For a key constructed with `new`, this is the user id it was
+    /// constructed with.
+    pub fn user_ids(&self) -> &[String] {
+        &self.user_ids
+    }
+
+    /// Add a user id to this key.
+    ///
+    /// This inserts a user id packet and a positive certification of it,
+    /// made at `unix_time`, after the key's other user ids. The
+    /// certification has the key flags, expiration and preferences of the
+    /// key's most recent self-signature. The sign function must sign data
+    /// with the private key paired with this key, as in `new`.
+    ///
+    /// If `primary` is true, the certification contains a primary user id
+    /// subpacket, so that OpenPGP implementations show this user id first.
+    /// See `primary_user_id`.
+    pub fn add_user_id<Sha256, F>(&mut self, user_id: &str, primary: bool, unix_time: u32, sign: F)
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let key_packet = self.key_packet();
+        let mut packets = key_packet.to_owned();
+        write_certification::<Sha256, _>(
+            &mut packets,
+            user_id.as_bytes(),
+            primary,
+            self.key_flags(),
+            unix_time,
+            self.key_expiration(),
+            &self.current_preferences(),
+            sign,
+        );
+        let packets = &packets[key_packet.len()..];
+
+        // user ids come before the subkeys
+        let mut offset = key_packet.len();
+        let mut index = 0;
+        for packet in Packets(&self.data[offset..]).map_while(Result::ok) {
+            if packet.tag == 14 {
+                break;
+            }
+            if packet.tag == 13 {
+                index += 1;
+            }
+            offset += packet.bytes.len();
+        }
+
+        self.data.splice(offset..offset, packets.iter().copied());
+        self.user_ids.insert(index, user_id.to_owned());
+    }
+
+    /// The user id this key designates as its primary user id.
+    ///
+    /// This is the user id whose self-certification has a primary user id
+    /// subpacket, or if several do, the one with the most recent such
+    /// certification. Returns `None` if no certification has one. The
+    /// self-certifications are not verified.
+    pub fn primary_user_id(&self) -> Option<&str> {
+        let fingerprint = self.fingerprint();
+        let mut user_id = None;
+        let mut user_ids = 0;
+        let mut primary: Option<(Option<u32>, usize)> = None;
+        for packet in Packets(&self.data).skip(1).map_while(Result::ok) {
+            match packet.tag {
+                13 => {
+                    user_id = Some(user_ids);
+                    user_ids += 1;
+                }
+                14 | 17 => user_id = None,
+                2 => {
+                    let Some(index) = user_id else {
+                        continue;
+                    };
+                    let Ok(sig) = PgpSig::from_bytes(packet.bytes) else {
+                        continue;
+                    };
+                    let is_primary = sig.fingerprint() == fingerprint
+                        && sig.try_sig_type().is_ok_and(SigType::is_certification)
+                        && sig
+                            .hashed_subpackets()
+                            .find(|subpacket| subpacket.tag == 25)
+                            .and_then(|subpacket| subpacket.data.first())
+                            .is_some_and(|&flag| flag != 0);
+                    if is_primary
+                        && primary.is_none_or(|(timestamp, _)| sig.timestamp() >= timestamp)
+                    {
+                        primary = Some((sig.timestamp(), index));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let (_, index) = primary?;
+        self.user_ids.get(index).map(String::as_str)
+    }
+
+    /// The ed25519 subkeys of this key, in the order they appear.
+    ///
+    /// Subkeys using other algorithms are not included.
+    pub fn subkeys(&self) -> &[Subkey] {
+        &self.subkeys
+    }
+
+    /// Add an ed25519 subkey to this key.
+    ///
+    /// This appends a public subkey packet and a subkey binding signature,
+    /// made with the primary key, to this key. The primary sign function
+    /// must sign data with the private key paired with this key.
+    ///
+    /// If the flags include `KeyFlags::SIGN`, the binding signature also
+    /// contains a primary key binding signature made by the subkey, as
+    /// OpenPGP requires for signing subkeys. The subkey sign function must
+    /// sign data with the private key paired with the subkey; it is not
+    /// called for subkeys which cannot sign.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the subkey is not 32 bytes of data.
+    pub fn add_subkey<Sha256, F1, F2>(
+        &mut self,
+        subkey: &[u8],
+        flags: KeyFlags,
+        unix_time: u32,
+        sign_with_primary: F1,
+        sign_with_subkey: F2,
+    ) where
+        Sha256: Digest<OutputSize = U32>,
+        F1: Fn(&[u8]) -> Signature,
+        F2: Fn(&[u8]) -> Signature,
+    {
+        assert!(subkey.len() == 32);
+
+        let mut packet = Vec::with_capacity(54);
+        write_public_key_packet(&mut packet, 14, subkey, unix_time);
+
+        let sig_data = subkey_binding_data(self.key_packet(), &packet);
+
+        let back_signature = if flags.contains(KeyFlags::SIGN) {
+            let mut key_packet = packet.clone();
+            key_packet[0] = 0x99;
+            Some(PgpSig::new::<Sha256, _>(
+                &sig_data,
+                fingerprint(&key_packet),
+                SigType::PrimaryKeyBinding,
+                HashAlgorithm::Sha256,
+                unix_time,
+                None,
+                &[],
+                sign_with_subkey,
+            ))
+        } else {
+            None
+        };
+
+        let flags = flags.to_octets();
+        let mut subpackets = vec![SubPacket {
+            tag: 27,
+            data: &flags,
+            critical: false,
+        }];
+        if let Some(back_signature) = &back_signature {
+            subpackets.push(SubPacket {
+                tag: 32,
+                data: &back_signature.as_bytes()[3..],
+                critical: false,
+            });
+        }
+
+        let binding = PgpSig::new::<Sha256, _>(
+            &sig_data,
+            self.fingerprint(),
+            SigType::SubkeyBinding,
+            HashAlgorithm::Sha256,
+            unix_time,
+            None,
+            &subpackets,
+            sign_with_primary,
+        );
+
+        self.data.extend(&packet);
+        self.data.extend(binding.as_bytes());
+        self.subkeys.push(Subkey::new(packet, Some(binding)));
+    }
+
+    /// Create a signature revoking this key.
+    ///
+    /// The signature contains a reason for revocation subpacket, with the
+    /// reason code and a human readable message explaining the revocation.
+    /// The sign function must sign data with the private key paired with
+    /// this key, as in `new`.
+    ///
+    /// A revocation signature is published by appending it to the key, or
+    /// distributing it alongside the key. A key parsed with a revocation
+    /// appended is only revoked once `add_embedded_revocation` checks it.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the message is too large to fit in a signature
+    /// packet; see `PgpSig::check_subpacket_sizes`.
+    pub fn revoke<Sha256, F>(
+        &self,
+        reason: RevocationReason,
+        message: &str,
+        unix_time: u32,
+        sign: F,
+    ) -> PgpSig
+    where
+        Sha256: Digest<OutputSize = U32>,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let mut reason_data = Vec::with_capacity(message.len() + 1);
+        reason_data.push(reason.as_byte());
+        reason_data.extend(message.as_bytes());
+
+        PgpSig::new::<Sha256, _>(
+            self.key_packet(),
+            self.fingerprint(),
+            SigType::KeyRevocation,
+            HashAlgorithm::Sha256,
+            unix_time,
+            None,
+            &[SubPacket {
+                tag: 29,
+                data: &reason_data,
+                critical: false,
+            }],
+            sign,
+        )
+    }
+
+    /// Verify that a subkey is bound to this key.
+    ///
+    /// This checks that the subkey's binding signature was made by this key
+    /// over this key and the subkey. The verify function is called as in
+    /// `verify`. The primary key binding signature of a signing subkey is
+    /// not checked.
+    pub fn verify_subkey_binding<D, F>(&self, subkey: &Subkey, verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        let binding = match subkey.binding_signature() {
+            Some(binding) => binding,
+            None => return false,
+        };
+        if binding.try_sig_type().ok() != Some(SigType::SubkeyBinding) {
+            return false;
+        }
+
+        let sig_data = subkey_binding_data(self.key_packet(), subkey.as_bytes());
+        self.verify::<D, _, _>(binding, |hasher| hasher.process(&sig_data), verify)
+    }
+
+    /// The key flags of this key.
+    ///
+    /// These are read from the key flags subpacket of the most recent
+    /// self-signature which has one. The self-signatures are not verified.
+    /// If no self-signature has key flags, this returns `KeyFlags::NONE`.
+    pub fn key_flags(&self) -> KeyFlags {
+        self.self_signatures()
+            .filter_map(|sig| {
+                let flags = sig
+                    .hashed_subpackets()
+                    .find(|subpacket| subpacket.tag == 27)?;
+                Some((sig.timestamp(), KeyFlags::from_octets(flags.data)))
+            })
+            .max_by_key(|&(timestamp, _)| timestamp)
+            .map_or(KeyFlags::NONE, |(_, flags)| flags)
+    }
+
+    /// The hash algorithms this key prefers, most preferred first.
+    ///
+    /// This is read from the most recent self-signature which has a
+    /// preferred hash algorithms subpacket; algorithms this library does
+    /// not support are left out. The self-signatures are not verified.
+    pub fn preferred_hash_algorithms(&self) -> Vec<HashAlgorithm> {
+        self.preferences(21)
+            .into_iter()
+            .filter_map(|id| HashAlgorithm::try_from(id).ok())
+            .collect()
+    }
+
+    /// The RFC 4880 IDs of the symmetric algorithms this key prefers, most
+    /// preferred first.
+    ///
+    /// See `preferred_hash_algorithms`.
+    pub fn preferred_symmetric_algorithms(&self) -> Vec<u8> {
+        self.preferences(11)
+    }
+
+    /// The RFC 4880 IDs of the compression algorithms this key prefers,
+    /// most preferred first.
+    ///
+    /// See `preferred_hash_algorithms`.
+    pub fn preferred_compression_algorithms(&self) -> Vec<u8> {
+        self.preferences(22)
+    }
+
+    /// The URI of the key server this key prefers updates to be fetched
+    /// from.
+    ///
+    /// This is read from the most recent self-signature which has a
+    /// preferred key server subpacket, and is `None` if there is none or
+    /// it is not UTF-8. The self-signatures are not verified.
+    pub fn preferred_key_server(&self) -> Option<&str> {
+        core::str::from_utf8(self.self_signature_subpacket(24)?).ok()
+    }
+
+    /// Whether this key asks key servers to accept changes to it only from
+    /// its owner.
+    ///
+    /// This is the no-modify flag of the key server preferences subpacket
+    /// in the most recent self-signature which has one. The
+    /// self-signatures are not verified.
+    pub fn key_server_no_modify(&self) -> bool {
+        self.self_signature_subpacket(23)
+            .and_then(|preferences| preferences.first())
+            .is_some_and(|flags| flags & 0x80 != 0)
+    }
+
+    /// The time at which this key expires, as a unix timestamp.
+    ///
+    /// This is the key's creation time plus the key expiration time from
+    /// the most recent self-signature which has one. Returns `None` if the
+    /// key does not expire. The self-signatures are not verified.
+    pub fn expires_at(&self) -> Option<u32> {
+        let expiration = self.key_expiration()?;
+        let expires_at = self.created_at() as u64 + expiration as u64;
+        Some(expires_at.min(u32::MAX as u64) as u32)
+    }
+
+    /// The creation time of this key, as a unix timestamp.
+    ///
+    /// The creation time is part of the data hashed into the fingerprint.
+    pub fn created_at(&self) -> u32 {
+        BigEndian::read_u32(&self.data[4..8])
     }
 
     /// All of the bytes in this key (including PGP metadata).
@@ -168,7 +696,179 @@ impl PgpKey {
 
     /// The OpenPGP fingerprint of this public key.
     pub fn fingerprint(&self) -> Fingerprint {
-        fingerprint(&self.data[0..54])
+        fingerprint(self.key_packet())
+    }
+
+    /// Verify data against a signature made by this key.
+    ///
+    /// This fails if the signature's fingerprint is not this key's
+    /// fingerprint, or if this key has been revoked with `add_revocation`.
+    /// A signature parsed with `PgpSig::from_bytes_legacy` which has no
+    /// fingerprint is instead matched by its key ID. Otherwise, the data is
+    /// hashed as in `PgpSig::verify`, and the verify function is called
+    /// with this key's ed25519 public key data, the hash, and the
+    /// signature.
+    pub fn verify<D, F1, F2>(&self, sig: &PgpSig, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if self.is_revoked() {
+            return false;
+        }
+
+        self.verify_unrevoked::<D, _, _>(sig, input, verify)
+    }
+
+    /// Verify data against a signature made by this key, using the given
+    /// ed25519 implementation.
+    ///
+    /// This behaves like `verify`, with `B::verify` as the verify
+    /// function. See the `backend` module.
+    pub fn verify_with<D, B, F>(&self, sig: &PgpSig, input: F) -> bool
+    where
+        D: Digest,
+        B: VerifyBackend,
+        F: FnOnce(&mut D),
+    {
+        self.verify::<D, _, _>(sig, input, B::verify)
+    }
+
+    /// Verify data against a signature made by this key, honoring the
+    /// key's expiration.
+    ///
+    /// This fails if the key has expired at the time `now`, or if the
+    /// signature was created at or after the key's expiration time.
+    /// Otherwise, it behaves like `verify`.
+    pub fn verify_at<D, F1, F2>(&self, sig: &PgpSig, now: u32, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if let Some(expires_at) = self.expires_at() {
+            if now >= expires_at || sig.timestamp().is_none_or(|t| t >= expires_at) {
+                return false;
+            }
+        }
+
+        self.verify::<D, _, _>(sig, input, verify)
+    }
+
+    /// Check whether a signature is a valid revocation of this key.
+    ///
+    /// The signature must be a key revocation signature made by this key
+    /// over its public key packet. The verify function is called as in
+    /// `verify`. This does not record the revocation; see `add_revocation`.
+    pub fn is_revoked_by<D, F>(&self, sig: &PgpSig, verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if sig.try_sig_type().ok() != Some(SigType::KeyRevocation) {
+            return false;
+        }
+
+        let key_packet = self.key_packet();
+        self.verify_unrevoked::<D, _, _>(sig, |hasher| hasher.process(key_packet), verify)
+    }
+
+    /// Check whether a signature is a valid revocation of a subkey of this
+    /// key.
+    ///
+    /// The signature must be a subkey revocation signature made by this key
+    /// over this key and the subkey. The verify function is called as in
+    /// `verify`.
+    pub fn is_subkey_revoked_by<D, F>(&self, subkey: &Subkey, sig: &PgpSig, verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if sig.try_sig_type().ok() != Some(SigType::SubkeyRevocation) {
+            return false;
+        }
+
+        let sig_data = subkey_binding_data(self.key_packet(), subkey.as_bytes());
+        self.verify_unrevoked::<D, _, _>(sig, |hasher| hasher.process(&sig_data), verify)
+    }
+
+    /// Revoke this key, if the signature is a valid revocation of it.
+    ///
+    /// The signature is checked as in `is_revoked_by`. If it is valid, it
+    /// is recorded, and every later call to `verify` on this key fails.
+    /// Returns whether the revocation was valid.
+    ///
+    /// Revocation signatures contained in the data a key is parsed from are
+    /// not checked, so they must be passed to this method, or checked with
+    /// `add_embedded_revocation`, to take effect.
+    pub fn add_revocation<D, F>(&mut self, sig: PgpSig, verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if !self.is_revoked_by::<D, _>(&sig, verify) {
+            return false;
+        }
+
+        self.revocation = Some(sig);
+        true
+    }
+
+    /// Revoke this key, if the data it was parsed from contains a valid
+    /// revocation of it.
+    ///
+    /// Each of `revocation_signatures` is checked as in `add_revocation`,
+    /// with the digest `D`, and the first valid one is recorded. Returns
+    /// whether one was found.
+    pub fn add_embedded_revocation<D, F>(&mut self, verify: F) -> bool
+    where
+        D: Digest,
+        F: Fn(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        let revocations: Vec<PgpSig> = self.revocation_signatures().collect();
+        revocations
+            .into_iter()
+            .any(|sig| self.add_revocation::<D, _>(sig, &verify))
+    }
+
+    /// The key revocation signatures contained in the data this key was
+    /// parsed from.
+    ///
+    /// These have not been checked; see `add_embedded_revocation`.
+    pub fn revocation_signatures(&self) -> impl Iterator<Item = PgpSig> + '_ {
+        Packets(&self.data)
+            .skip(1)
+            .map_while(Result::ok)
+            .filter(|packet| packet.tag == 2)
+            .filter_map(|packet| PgpSig::from_bytes(packet.bytes).ok())
+            .filter(|sig| sig.try_sig_type().ok() == Some(SigType::KeyRevocation))
+    }
+
+    /// Whether a valid revocation has been added to this key.
+    pub fn is_revoked(&self) -> bool {
+        self.revocation.is_some()
+    }
+
+    /// The revocation signature added to this key, if any.
+    ///
+    /// Use `PgpSig::revocation_reason` to find out why the key was revoked.
+    pub fn revocation(&self) -> Option<&PgpSig> {
+        self.revocation.as_ref()
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Verify data against a signature made by this key, using
+    /// ed25519-dalek.
+    ///
+    /// This fails if the signature's fingerprint is not this key's
+    /// fingerprint, or if this key is not a valid ed25519 public key.
+    pub fn verify_dalek<D, F>(&self, sig: &PgpSig, input: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&mut D),
+    {
+        self.verify::<D, _, _>(sig, input, Dalek::verify)
     }
 
     #[cfg(feature = "dalek")]
@@ -192,6 +892,45 @@ impl PgpKey {
         )
     }
 
+    #[cfg(feature = "getrandom")]
+    /// Generate a new ed25519 key, returning it with its PgpKey.
+    ///
+    /// The key is generated with randomness from the operating system,
+    /// through `getrandom`. It is otherwise the same as `generate_with`.
+    pub fn generate<Sha256, Sha512>(
+        flags: KeyFlags,
+        unix_time: u32,
+        user_id: &str,
+    ) -> (PgpKey, dalek::SigningKey)
+    where
+        Sha256: Digest<OutputSize = U32>,
+        Sha512: Digest<OutputSize = U64>,
+    {
+        PgpKey::generate_with::<Sha256, Sha512, _>(&mut rand_core::OsRng, flags, unix_time, user_id)
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Generate a new ed25519 key with the given random number generator,
+    /// returning it with its PgpKey.
+    ///
+    /// The PgpKey is made from the new key as in `from_dalek`. The secret
+    /// key is only returned, never stored; keep it to sign with the key.
+    pub fn generate_with<Sha256, Sha512, R>(
+        rng: &mut R,
+        flags: KeyFlags,
+        unix_time: u32,
+        user_id: &str,
+    ) -> (PgpKey, dalek::SigningKey)
+    where
+        Sha256: Digest<OutputSize = U32>,
+        Sha512: Digest<OutputSize = U64>,
+        R: rand_core::CryptoRng + rand_core::RngCore,
+    {
+        let keypair = dalek::SigningKey::generate(rng);
+        let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, flags, unix_time, user_id);
+        (key, keypair)
+    }
+
     #[cfg(feature = "dalek")]
     /// Convert this key into a dalek PublicKey.
     ///
@@ -199,6 +938,104 @@ impl PgpKey {
     pub fn to_dalek(&self) -> Result<dalek::VerifyingKey, dalek::SignatureError> {
         dalek::VerifyingKey::from_bytes(&self.key_data())
     }
+
+    fn verify_unrevoked<D, F1, F2>(&self, sig: &PgpSig, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if !sig.is_issued_by(&self.fingerprint()) {
+            return false;
+        }
+
+        let key = self.key_data();
+        sig.verify::<D, _, _>(input, |data, signature| verify(&key, data, signature))
+    }
+
+    // The key expiration time from the most recent self-signature with one,
+    // in seconds after the key's creation. Zero means the key never expires.
+    fn key_expiration(&self) -> Option<u32> {
+        self.self_signatures()
+            .filter_map(|sig| {
+                let expiration = sig
+                    .hashed_subpackets()
+                    .find(|subpacket| subpacket.tag == 9)?;
+                if expiration.data.len() != 4 {
+                    return None;
+                }
+                Some((sig.timestamp(), BigEndian::read_u32(expiration.data)))
+            })
+            .max_by_key(|&(timestamp, _)| timestamp)
+            .map(|(_, expiration)| expiration)
+            .filter(|&expiration| expiration != 0)
+    }
+
+    // The full set of preferences read back from the self-signatures, for
+    // writing a new certification which keeps them.
+    fn current_preferences(&self) -> Preferences {
+        Preferences {
+            hash_algorithms: self.preferred_hash_algorithms(),
+            symmetric_algorithms: self.preferred_symmetric_algorithms(),
+            compression_algorithms: self.preferred_compression_algorithms(),
+            key_server: self.preferred_key_server().map(ToOwned::to_owned),
+            key_server_no_modify: self.key_server_no_modify(),
+        }
+    }
+
+    // The algorithm IDs in the preference subpacket with this tag, from the
+    // most recent self-signature which has one.
+    fn preferences(&self, tag: u8) -> Vec<u8> {
+        self.self_signature_subpacket(tag)
+            .map_or_else(Vec::new, ToOwned::to_owned)
+    }
+
+    // The data of the hashed subpacket with this tag in the most recent
+    // self-signature which has one.
+    fn self_signature_subpacket(&self, tag: u8) -> Option<&[u8]> {
+        self.self_signature_packets()
+            .filter_map(|(sig, body)| {
+                // the subpacket is borrowed from the key's data, rather
+                // than from the parsed signature
+                let hashed_len = BigEndian::read_u16(&body[4..6]) as usize;
+                let subpacket = SubPacketIter {
+                    data: &body[6..(6 + hashed_len)],
+                }
+                .find(|subpacket| subpacket.tag == tag)?;
+                Some((sig.timestamp(), subpacket.data))
+            })
+            .max_by_key(|&(timestamp, _)| timestamp)
+            .map(|(_, data)| data)
+    }
+
+    // The public key packet, with its header.
+    fn key_packet(&self) -> &[u8] {
+        let len = BigEndian::read_u16(&self.data[1..3]) as usize;
+        &self.data[..(3 + len)]
+    }
+
+    // The certifications and direct key signatures following the primary
+    // key which were issued by the primary key.
+    fn self_signatures(&self) -> impl Iterator<Item = PgpSig> + '_ {
+        self.self_signature_packets().map(|(sig, _)| sig)
+    }
+
+    // The self-signatures, each with the body of the packet it was parsed
+    // from.
+    fn self_signature_packets(&self) -> impl Iterator<Item = (PgpSig, &[u8])> + '_ {
+        let fingerprint = self.fingerprint();
+        Packets(&self.data)
+            .skip(1)
+            .map_while(Result::ok)
+            .filter(|packet| packet.tag == 2)
+            .filter_map(|packet| Some((PgpSig::from_bytes(packet.bytes).ok()?, packet.body)))
+            .filter(move |(sig, _)| sig.fingerprint() == fingerprint)
+            .filter(|(sig, _)| {
+                sig.try_sig_type().is_ok_and(|sig_type| {
+                    sig_type.is_certification() || sig_type == SigType::DirectlyOnKey
+                })
+            })
+    }
 }
 
 impl Debug for PgpKey {
@@ -209,17 +1046,20 @@ impl Debug for PgpKey {
     }
 }
 
+#[cfg(feature = "std")]
 impl Display for PgpKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         ascii_armor(
             "BEGIN PGP PUBLIC KEY BLOCK",
             "END PGP PUBLIC KEY BLOCK",
+            &[],
             &self.data[..],
             f,
         )
     }
 }
 
+#[cfg(feature = "std")]
 impl FromStr for PgpKey {
     type Err = PgpError;
     fn from_str(s: &str) -> Result<PgpKey, PgpError> {
@@ -227,8 +1067,14 @@ impl FromStr for PgpKey {
     }
 }
 
-fn write_public_key_packet(data: &mut Vec<u8>, key: &[u8], unix_time: u32) -> Range<usize> {
-    write_packet(data, 6, |packet| {
+// Writes a public key (tag 6) or public subkey (tag 14) packet.
+pub(crate) fn write_public_key_packet(
+    data: &mut Vec<u8>,
+    tag: u8,
+    key: &[u8],
+    unix_time: u32,
+) -> Range<usize> {
+    write_packet(data, tag, |packet| {
         packet.push(4); // packet version #4
         packet.extend(&bigendian_u32(unix_time));
         packet.push(22); // algorithm id #22 (edDSA)
@@ -242,14 +1088,165 @@ fn write_public_key_packet(data: &mut Vec<u8>, key: &[u8], unix_time: u32) -> Ra
     })
 }
 
-fn write_user_id_packet(data: &mut Vec<u8>, user_id: &str) -> Range<usize> {
-    write_packet(data, 13, |packet| packet.extend(user_id.as_bytes()))
+// Writes a user id packet and a positive certification of it by the public
+// key packet at the start of the data.
+#[allow(clippy::too_many_arguments)]
+fn write_certification<Sha256, F>(
+    data: &mut Vec<u8>,
+    user_id: &[u8],
+    primary: bool,
+    flags: KeyFlags,
+    unix_time: u32,
+    expiration: Option<u32>,
+    preferences: &Preferences,
+    sign: F,
+) where
+    Sha256: Digest<OutputSize = U32>,
+    F: Fn(&[u8]) -> Signature,
+{
+    let key_packet = data[..(3 + BigEndian::read_u16(&data[1..3]) as usize)].to_owned();
+    let fingerprint = fingerprint(&key_packet);
+    write_user_id_packet(data, user_id);
+
+    let sig_data = {
+        let mut data = key_packet;
+        data.extend(&[0xb4]);
+        data.extend(&bigendian_u32(user_id.len() as u32));
+        data.extend(user_id);
+        data
+    };
+
+    let flags = flags.to_octets();
+    let expiration = expiration.map(bigendian_u32);
+    let mut subpackets = vec![SubPacket {
+        tag: 27,
+        data: &flags,
+        critical: false,
+    }];
+    if preferences.key_server_no_modify {
+        subpackets.push(SubPacket {
+            tag: 23,
+            data: &[0x80],
+            critical: false,
+        });
+    }
+    if let Some(expiration) = &expiration {
+        subpackets.push(SubPacket {
+            tag: 9,
+            data: expiration,
+            critical: false,
+        });
+    }
+
+    let hash_algorithms: Vec<u8> = preferences
+        .hash_algorithms
+        .iter()
+        .map(|algorithm| algorithm.as_byte())
+        .collect();
+    for (tag, algorithms) in [
+        (11, &preferences.symmetric_algorithms[..]),
+        (21, &hash_algorithms[..]),
+        (22, &preferences.compression_algorithms[..]),
+    ] {
+        if !algorithms.is_empty() {
+            subpackets.push(SubPacket {
+                tag,
+                data: algorithms,
+                critical: false,
+            });
+        }
+    }
+    if let Some(key_server) = &preferences.key_server {
+        subpackets.push(SubPacket {
+            tag: 24,
+            data: key_server.as_bytes(),
+            critical: false,
+        });
+    }
+    if primary {
+        subpackets.push(SubPacket {
+            tag: 25,
+            data: &[1],
+            critical: false,
+        });
+    }
+
+    let signature_packet = PgpSig::new::<Sha256, _>(
+        &sig_data,
+        fingerprint,
+        SigType::PositiveCertification,
+        HashAlgorithm::Sha256,
+        unix_time,
+        None,
+        &subpackets,
+        sign,
+    );
+
+    data.extend(signature_packet.as_bytes());
+}
+
+fn write_user_id_packet(data: &mut Vec<u8>, user_id: &[u8]) -> Range<usize> {
+    write_packet(data, 13, |packet| packet.extend(user_id))
+}
+
+// Reads the user id packets following the public key packet. Reading stops
+// at the first malformed packet.
+fn read_user_ids(data: &[u8]) -> Result<Vec<String>, PgpError> {
+    Packets(data)
+        .skip(1)
+        .map_while(Result::ok)
+        .filter(|packet| packet.tag == 13)
+        .map(|packet| {
+            let user_id = core::str::from_utf8(packet.body).map_err(|_| PgpError::InvalidUserId)?;
+            Ok(user_id.to_owned())
+        })
+        .collect()
+}
+
+// Reads the ed25519 subkeys following the public key packet, with the first
+// subkey binding signature following each. Reading stops at the first
+// malformed packet.
+fn read_subkeys(data: &[u8]) -> Vec<Subkey> {
+    let mut subkeys: Vec<Subkey> = vec![];
+    let mut in_subkey = false;
+    for packet in Packets(data).skip(1).map_while(Result::ok) {
+        match packet.tag {
+            14 => {
+                in_subkey = is_ed25519_valid(packet.body);
+                if in_subkey {
+                    let body = packet.body;
+                    let packet = prepare_packet(14, |data| data.extend(body));
+                    subkeys.push(Subkey::new(packet, None));
+                }
+            }
+            2 if in_subkey => {
+                let sig = match PgpSig::from_bytes(packet.bytes) {
+                    Ok(sig) if sig.try_sig_type().ok() == Some(SigType::SubkeyBinding) => sig,
+                    _ => continue,
+                };
+                subkeys.last_mut().unwrap().set_binding(sig);
+                in_subkey = false;
+            }
+            _ => {}
+        }
+    }
+    subkeys
+}
+
+// The data hashed for a subkey binding or primary key binding signature:
+// both key packets, with the headers of public key packets.
+fn subkey_binding_data(key_packet: &[u8], subkey_packet: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(key_packet.len() + subkey_packet.len());
+    data.extend(key_packet);
+    data.push(0x99);
+    data.extend(&subkey_packet[1..]);
+    data
 }
 
 // Mainly this function parses the possible packet headers.
-// If the data begins with a valid old public key packet using
-// anything but the indeterminate length header format, it
-// will return the data of that public key packet.
+// If the data begins with a valid public key packet using
+// anything but the indeterminate or partial length header
+// formats, it will return the data of that public key packet.
 fn find_public_key_packet(data: &[u8]) -> Result<(&[u8], usize), PgpError> {
     let (init, len) = match data.first() {
         Some(&0x98) => {
@@ -276,6 +1273,10 @@ fn find_public_key_packet(data: &[u8]) -> Result<(&[u8], usize), PgpError> {
             }
             (5, len)
         }
+        Some(&0xc6) => {
+            let (len_len, len) = read_new_format_length(&data[1..])?;
+            (1 + len_len, len)
+        }
         _ => return Err(PgpError::UnsupportedPacketLength),
     };
     let end = init + len;
@@ -285,16 +1286,54 @@ fn find_public_key_packet(data: &[u8]) -> Result<(&[u8], usize), PgpError> {
     Ok((&data[init..end], end))
 }
 
-fn fingerprint(key_packet: &[u8]) -> [u8; 20] {
+/// Compute the OpenPGP fingerprint of an ed25519 public key.
+///
+/// This is the fingerprint gpg assigns to a version 4 EdDSA key with this
+/// public key, created at the unix time `created_at`: the SHA-1 hash of
+/// the public key packet with a `0x99` header. It is the same as
+/// `PgpKey::fingerprint`, without constructing the key.
+pub fn fingerprint_from_ed25519(public_key: &[u8; 32], created_at: u32) -> Fingerprint {
+    let mut key_packet = Vec::with_capacity(54);
+    write_public_key_packet(&mut key_packet, 6, public_key, created_at);
+    fingerprint(&key_packet)
+}
+
+pub(crate) fn fingerprint(key_packet: &[u8]) -> Fingerprint {
     let mut hasher = Sha1::new();
     hasher.update(key_packet);
-    hasher.digest().bytes()
+    Fingerprint(hasher.digest().bytes())
+}
+
+#[cfg(feature = "subtle")]
+pub(crate) fn fingerprints_match(a: &Fingerprint, b: &Fingerprint) -> bool {
+    a.ct_eq(b).into()
 }
 
-fn is_ed25519_valid(packet: &[u8]) -> bool {
+#[cfg(not(feature = "subtle"))]
+pub(crate) fn fingerprints_match(a: &Fingerprint, b: &Fingerprint) -> bool {
+    a == b
+}
+
+// Whether a public key packet body is a version 4 ed25519 key: either an
+// EdDSA (22) key on curve25519, or with the crypto-refresh feature, an
+// Ed25519 (27) key from RFC 9580.
+pub(crate) fn is_ed25519_valid(packet: &[u8]) -> bool {
+    if packet.len() == 38 && packet[0] == 0x04 && packet[5] == 27 {
+        return cfg!(feature = "crypto-refresh");
+    }
     packet.len() == 51
         && packet[0] == 0x04
         && packet[5] == 0x16
         && &packet[6..16] == CURVE
         && packet[16..19] == [0x01, 0x07, 0x40]
 }
+
+// The ed25519 public key in a public key packet body which passed
+// is_ed25519_valid. Ed25519 keys store it as raw octets, EdDSA keys as an
+// MPI with a 0x40 prefix.
+pub(crate) fn ed25519_key(packet: &[u8]) -> [u8; 32] {
+    let init = if packet[5] == 27 { 6 } else { 19 };
+    let mut key = [0; 32];
+    key.copy_from_slice(&packet[init..(init + 32)]);
+    key
+}
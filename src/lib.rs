@@ -7,32 +7,120 @@
 //! PGP formatted. If you don't want to use a heavyweight dependency like gpg,
 //! this library supports only the minimal necessary components of the PGP
 //! format to transmit your keys and signatures.
+//!
+//! Without the default `std` feature, this library only requires `alloc`.
+//! Signatures and keys can still be constructed, parsed from binary data
+//! and verified, but ASCII armor and the `cleartext` and `detached`
+//! modules are unavailable.
+//!
+//! Keys and signatures use the EdDSA algorithm (22) on curve25519. With the
+//! `crypto-refresh` feature, version 4 keys and signatures using the
+//! Ed25519 algorithm (27) from RFC 9580 can also be parsed and verified,
+//! and the `v6` feature adds `PgpSigV6` for version 6 signatures.
+//!
+//! This library never handles secret keys itself, except for the
+//! ed25519-dalek keys passed to the `dalek` conversions or returned by
+//! `PgpKey::generate_with`. The `getrandom` feature adds `PgpKey::generate`,
+//! which generates keys with randomness from the operating system. With the
+//! `zeroize` feature, those keys are cleared when they are dropped, as are
+//! the buffers of file contents read by the `detached` module.
+//!
+//! Verification takes a function which checks the ed25519 signature. The
+//! `backend` module provides them for ed25519-dalek, ring and
+//! ed25519-compact, behind the `dalek`, `ring` and `ed25519-compact`
+//! features.
+//!
+//! Data is hashed with any hasher implementing version 0.7 of the `Digest`
+//! trait. The `sha3` feature adds the `sha3` module, with hashers for
+//! SHA3-256 and SHA3-512.
+//!
+//! The `wasm` feature adds the `wasm` module, which exports signature
+//! verification to JavaScript for use in the browser.
+#![no_std]
 #![deny(missing_docs, missing_debug_implementations)]
 // Otherwise, bitflags! complains about a 0x0 value
 #![allow(clippy::bad_bit_mask)]
 
 #[macro_use]
-extern crate failure;
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 #[macro_use]
 extern crate bitflags;
 
+#[cfg(feature = "ed25519-compact")]
+extern crate ed25519_compact;
 #[cfg(feature = "dalek")]
 extern crate ed25519_dalek as dalek;
+#[cfg(feature = "dalek")]
+extern crate rand_core;
+#[cfg(feature = "ring")]
+extern crate ring;
+#[cfg(feature = "wasm")]
+extern crate sha2;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
 
-mod ascii_armor;
-mod packet;
+#[cfg(feature = "std")]
+pub mod ascii_armor;
+pub mod backend;
+#[cfg(feature = "std")]
+pub mod cleartext;
+#[cfg(feature = "std")]
+pub mod detached;
+mod fingerprint;
+pub mod packet;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "sha3")]
+pub mod sha3;
 
+mod builder;
 mod key;
+mod notation;
 mod sig;
+mod subkey;
+#[cfg(feature = "v6")]
+mod v6;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use crate::key::PgpKey;
-pub use crate::sig::{PgpSig, SigType, SubPacket};
+pub use crate::backend::VerifyBackend;
+pub use crate::builder::SigBuilder;
+pub use crate::fingerprint::{Fingerprint, KeyId};
+pub use crate::key::{fingerprint_from_ed25519, PgpKey, Preferences};
+pub use crate::notation::Notation;
+pub use crate::sig::{
+    HashAlgorithm, PgpSig, PgpSigRef, RevocationReason, SigType, SubPacket, UnsignedSig, Verifier,
+};
+pub use crate::subkey::Subkey;
+#[cfg(feature = "v6")]
+pub use crate::v6::PgpSigV6;
 
-/// An OpenPGP public key fingerprint.
-pub type Fingerprint = [u8; 20];
 /// An ed25519 signature.
 pub type Signature = [u8; 64];
 
+#[cfg(feature = "subtle")]
+/// Compare two signatures in constant time.
+pub fn signature_ct_eq(a: &Signature, b: &Signature) -> bool {
+    use subtle::ConstantTimeEq;
+    a[..].ct_eq(&b[..]).into()
+}
+
+#[cfg(feature = "std")]
+/// The time given by the `SOURCE_DATE_EPOCH` environment variable.
+///
+/// Reproducible build systems set this variable to a fixed unix time, to be
+/// used in place of the current time. Using it as the time of a signature
+/// makes the signature reproducible. Returns `None` if the variable is not
+/// set, or is not a unix time which fits in 32 bits.
+pub fn source_date_epoch() -> Option<u32> {
+    std::env::var("SOURCE_DATE_EPOCH").ok()?.trim().parse().ok()
+}
+
 bitflags! {
     /// The key flags assigned to this key.
     pub struct KeyFlags: u8 {
@@ -51,34 +139,357 @@ bitflags! {
     }
 }
 
+impl KeyFlags {
+    /// Read key flags from the data of a key flags subpacket.
+    ///
+    /// The key flags are a string of octets of any length, but every flag
+    /// this library knows is in the first octet; the other octets, and
+    /// unknown flags in the first octet, are ignored. An empty string has
+    /// no flags.
+    pub fn from_octets(octets: &[u8]) -> KeyFlags {
+        octets
+            .first()
+            .map_or(KeyFlags::NONE, |&flags| KeyFlags::from_bits_truncate(flags))
+    }
+
+    /// Write these key flags as the data of a key flags subpacket.
+    ///
+    /// This is always a single octet.
+    pub fn to_octets(self) -> alloc::vec::Vec<u8> {
+        vec![self.bits()]
+    }
+}
+
+impl core::fmt::Display for KeyFlags {
+    /// Writes the names of the flags separated by commas, such as
+    /// `certify, sign`, or `none` if there are no flags.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        const NAMES: &[(KeyFlags, &str)] = &[
+            (KeyFlags::CERTIFY, "certify"),
+            (KeyFlags::SIGN, "sign"),
+            (KeyFlags::ENCRYPT_COMS, "encrypt communications"),
+            (KeyFlags::ENCRYPT_STORAGE, "encrypt storage"),
+            (KeyFlags::AUTHENTICATION, "authenticate"),
+        ];
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+        let mut names = NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name);
+        if let Some(name) = names.next() {
+            f.write_str(name)?;
+        }
+        for name in names {
+            f.write_str(", ")?;
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
 /// An error returned while attempting to parse a PGP signature or public key.
-#[derive(Fail, Debug)]
+#[derive(Debug)]
 pub enum PgpError {
     /// Invalid ASCII armor format
-    #[fail(display = "Invalid ASCII armor format")]
     InvalidAsciiArmor,
+    #[cfg(feature = "std")]
+    /// ASCII armored data is not valid base64
+    InvalidBase64(base64::DecodeError),
+    /// ASCII armor checksum does not match the armored data
+    ChecksumMismatch,
     /// Packet header incorrectly formatted
-    #[fail(display = "Packet header incorrectly formatted")]
     InvalidPacketHeader,
     /// Unsupported packet length format
-    #[fail(display = "Unsupported packet length format")]
     UnsupportedPacketLength,
+    /// Packet is not of the expected type
+    UnexpectedPacketType {
+        /// The tag of the expected packet type
+        expected: u8,
+        /// The tag of the packet found
+        found: u8,
+    },
+    /// Packet uses a partial body length, which is not supported
+    UnsupportedPartialLength,
     /// Unsupported form of signature packet
-    #[fail(display = "Unsupported form of signature packet")]
     UnsupportedSignaturePacket,
     /// First hashed subpacket of signature must be the key fingerprint
-    #[fail(display = "First hashed subpacket of signature must be the key fingerprint")]
     MissingFingerprintSubpacket,
+    /// Signature packet is not a version 4 signature
+    UnexpectedSignatureVersion(u8),
+    /// Signature packet was not made with ed25519
+    UnexpectedPublicKeyAlgorithm(u8),
+    /// Hashed subpacket length runs past the end of the signature packet
+    HashedSubpacketLengthOverflow,
+    /// Signature packet contains data after the signature
+    TrailingDataAfterSignature,
+    /// MPI length prefix is inconsistent with the data present
+    MalformedMpi,
+    /// Data continues after the end of the packet
+    TrailingData,
+    /// Data ended before the end of the packet
+    UnexpectedEof,
+    /// Packet header is not in the form this library writes
+    NonCanonicalHeader,
+    /// Digest length does not match the signature's hash algorithm
+    HashAlgorithmMismatch,
+    /// Hash prefix in the signature does not match the computed digest
+    HashPrefixMismatch,
+    /// Salt length does not match the signature's hash algorithm
+    InvalidSalt,
+    /// Subpackets are too large to fit in a signature packet
+    SubpacketTooLarge,
     /// Unsupported form of public key packet
-    #[fail(display = "Unsupported form of public key packet")]
     UnsupportedPublicKeyPacket,
+    /// Notation is malformed or has an invalid name or value
+    InvalidNotation,
+    /// Public key has no user id packet
+    MissingUserId,
+    /// User id is not valid UTF-8
+    InvalidUserId,
+    /// Issuer fingerprints in the hashed and unhashed sections differ
+    FingerprintMismatch,
+    /// Signatures differ in their hashed sections or signatures
+    SignatureMismatch,
+    /// Fingerprint is not forty hex digits
+    InvalidFingerprint,
+    /// Invalid cleartext signed document
+    InvalidCleartext,
+    #[cfg(feature = "std")]
+    /// An I/O error occurred
+    Io(std::io::Error),
+    /// Signature type byte does not correspond to a known signature type
+    UnknownSignatureType(u8),
+    /// Hash algorithm is not supported
+    UnsupportedHashAlgorithm(u8),
+    /// Hash algorithm is too weak to be trusted
+    WeakHashAlgorithm(u8),
+    /// Hash algorithm is not one of the allowed algorithms
+    DisallowedHashAlgorithm(u8),
+    /// Signature has a critical subpacket which is not recognized
+    UnknownCriticalSubpacket(u8),
+    /// Reason for revocation code is not a known reason
+    UnknownRevocationReason(u8),
+}
+
+impl core::fmt::Display for PgpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            PgpError::InvalidAsciiArmor => f.write_str("Invalid ASCII armor format"),
+            #[cfg(feature = "std")]
+            PgpError::InvalidBase64(err) => write!(f, "Invalid base64 in ASCII armor: {}", err),
+            PgpError::ChecksumMismatch => {
+                f.write_str("ASCII armor checksum does not match the armored data")
+            }
+            PgpError::InvalidPacketHeader => f.write_str("Packet header incorrectly formatted"),
+            PgpError::UnsupportedPacketLength => f.write_str("Unsupported packet length format"),
+            PgpError::UnexpectedPacketType { expected, found } => {
+                write!(f, "Expected packet type {}, found {}", expected, found)
+            }
+            PgpError::UnsupportedPartialLength => {
+                f.write_str("Packet uses a partial body length, which is not supported")
+            }
+            PgpError::UnsupportedSignaturePacket => {
+                f.write_str("Unsupported form of signature packet")
+            }
+            PgpError::MissingFingerprintSubpacket => {
+                f.write_str("First hashed subpacket of signature must be the key fingerprint")
+            }
+            PgpError::UnexpectedSignatureVersion(version) => {
+                write!(f, "Unexpected signature version: {}", version)
+            }
+            PgpError::UnexpectedPublicKeyAlgorithm(algorithm) => {
+                write!(f, "Unexpected public key algorithm: {}", algorithm)?;
+                match public_key_algorithm_name(*algorithm) {
+                    Some(name) => write!(f, " ({})", name),
+                    None => Ok(()),
+                }
+            }
+            PgpError::HashedSubpacketLengthOverflow => {
+                f.write_str("Hashed subpacket length runs past the end of the signature packet")
+            }
+            PgpError::TrailingDataAfterSignature => {
+                f.write_str("Signature packet contains data after the signature")
+            }
+            PgpError::MalformedMpi => {
+                f.write_str("MPI length prefix is inconsistent with the data present")
+            }
+            PgpError::TrailingData => f.write_str("Data continues after the end of the packet"),
+            PgpError::UnexpectedEof => f.write_str("Data ended before the end of the packet"),
+            PgpError::NonCanonicalHeader => {
+                f.write_str("Packet header is not in the form this library writes")
+            }
+            PgpError::HashAlgorithmMismatch => {
+                f.write_str("Digest length does not match the signature's hash algorithm")
+            }
+            PgpError::HashPrefixMismatch => {
+                f.write_str("Hash prefix in the signature does not match the computed digest")
+            }
+            PgpError::InvalidSalt => {
+                f.write_str("Salt length does not match the signature's hash algorithm")
+            }
+            PgpError::SubpacketTooLarge => {
+                f.write_str("Subpackets are too large to fit in a signature packet")
+            }
+            PgpError::UnsupportedPublicKeyPacket => {
+                f.write_str("Unsupported form of public key packet")
+            }
+            PgpError::InvalidNotation => {
+                f.write_str("Notation is malformed or has an invalid name or value")
+            }
+            PgpError::MissingUserId => f.write_str("Public key has no user id packet"),
+            PgpError::InvalidUserId => f.write_str("User id is not valid UTF-8"),
+            PgpError::FingerprintMismatch => {
+                f.write_str("Issuer fingerprints in the hashed and unhashed sections differ")
+            }
+            PgpError::SignatureMismatch => {
+                f.write_str("Signatures differ in their hashed sections or signatures")
+            }
+            PgpError::InvalidFingerprint => f.write_str("Fingerprint is not forty hex digits"),
+            PgpError::InvalidCleartext => f.write_str("Invalid cleartext signed document"),
+            #[cfg(feature = "std")]
+            PgpError::Io(err) => write!(f, "I/O error: {}", err),
+            PgpError::UnknownSignatureType(byte) => {
+                write!(f, "Unknown signature type: {:#04x}", byte)
+            }
+            PgpError::UnsupportedHashAlgorithm(byte) => {
+                write!(f, "Unsupported hash algorithm: {}", byte)
+            }
+            PgpError::WeakHashAlgorithm(byte) => {
+                write!(f, "Hash algorithm is too weak to be trusted: {}", byte)
+            }
+            PgpError::DisallowedHashAlgorithm(byte) => {
+                write!(f, "Hash algorithm is not allowed: {}", byte)
+            }
+            PgpError::UnknownCriticalSubpacket(tag) => {
+                write!(f, "Unknown critical subpacket: {}", tag)
+            }
+            PgpError::UnknownRevocationReason(byte) => {
+                write!(f, "Unknown revocation reason: {}", byte)
+            }
+        }
+    }
+}
+
+// The name of an OpenPGP public key algorithm, for error messages.
+fn public_key_algorithm_name(algorithm: u8) -> Option<&'static str> {
+    Some(match algorithm {
+        1..=3 => "RSA",
+        16 => "Elgamal",
+        17 => "DSA",
+        18 => "ECDH",
+        19 => "ECDSA",
+        22 => "EdDSA",
+        25 => "X25519",
+        26 => "X448",
+        27 => "Ed25519",
+        28 => "Ed448",
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PgpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PgpError::InvalidBase64(err) => Some(err),
+            PgpError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The reason a signature was rejected by `PgpSig::verify_detailed`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum VerifyError {
+    /// Signature was made by a key with a different fingerprint
+    WrongKey,
+    /// Signature has a critical subpacket which is not recognized
+    UnknownCriticalSubpacket(u8),
+    /// Signature expired before the time of verification
+    Expired,
+    /// Signature was made with a hash algorithm which is not allowed
+    DisallowedHashAlgorithm(u8),
+    /// Digest length does not match the signature's hash algorithm
+    HashAlgorithmMismatch,
+    /// Hash prefix in the signature does not match the computed digest
+    HashPrefixMismatch,
+    /// Signature is not a valid signature of the digest
+    BadSignature,
+}
+
+impl core::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            VerifyError::WrongKey => {
+                f.write_str("Signature was made by a key with a different fingerprint")
+            }
+            VerifyError::UnknownCriticalSubpacket(tag) => {
+                write!(f, "Unknown critical subpacket: {}", tag)
+            }
+            VerifyError::Expired => f.write_str("Signature has expired"),
+            VerifyError::DisallowedHashAlgorithm(byte) => {
+                write!(f, "Hash algorithm is not allowed: {}", byte)
+            }
+            VerifyError::HashAlgorithmMismatch => {
+                f.write_str("Digest length does not match the signature's hash algorithm")
+            }
+            VerifyError::HashPrefixMismatch => {
+                f.write_str("Hash prefix in the signature does not match the computed digest")
+            }
+            VerifyError::BadSignature => f.write_str("Signature is not valid"),
+        }
+    }
 }
 
-// Helper for writing base64 data
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+/// An error returned while constructing a signature with a fallible sign
+/// function.
+#[derive(Debug)]
+pub enum SignError<E> {
+    /// The sign function returned an error.
+    Signer(E),
+    /// The signature could not be encoded, such as because its subpackets
+    /// are too large.
+    Packet(PgpError),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for SignError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            SignError::Signer(err) => write!(f, "Signing failed: {}", err),
+            SignError::Packet(err) => write!(f, "Could not encode signature: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for SignError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SignError::Signer(err) => Some(err),
+            SignError::Packet(err) => Some(err),
+        }
+    }
+}
+
+// Helper for writing base64 data; without std, the data is written as hex
 struct Base64<'a>(&'a [u8]);
 
-impl<'a> std::fmt::Debug for Base64<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for Base64<'a> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.write_str(&base64::encode(self.0))
     }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
 }
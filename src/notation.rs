@@ -0,0 +1,95 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::packet::bigendian_u16;
+use crate::PgpError;
+
+/// A notation attached to a signature.
+///
+/// Notations are name/value pairs carried in notation data subpackets (tag
+/// 20). Names chosen by users must have the form `name@domain`, such as
+/// `pipeline@pkgx.dev`, so that they do not collide with names defined by
+/// the IETF.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Notation {
+    /// The name of the notation.
+    pub name: String,
+    /// The value of the notation.
+    pub value: Vec<u8>,
+    /// Whether the value is human readable text.
+    pub human_readable: bool,
+}
+
+impl Notation {
+    /// The tag of notation data subpackets.
+    pub const TAG: u8 = 20;
+
+    /// Construct a notation with a human readable value.
+    pub fn text(name: &str, value: &str) -> Notation {
+        Notation {
+            name: name.to_owned(),
+            value: value.as_bytes().to_owned(),
+            human_readable: true,
+        }
+    }
+
+    /// Encode this notation as the data of a notation data subpacket.
+    ///
+    /// Use this as the data of a `SubPacket` with the tag `Notation::TAG`.
+    /// This returns an error if the name is not of the form `name@domain`,
+    /// if the value is marked human readable but is not valid UTF-8, or if
+    /// the name or value is too long to encode.
+    pub fn to_subpacket(&self) -> Result<Vec<u8>, PgpError> {
+        let is_user_name = match self.name.split_once('@') {
+            Some((name, domain)) => !name.is_empty() && !domain.is_empty() && !domain.contains('@'),
+            None => false,
+        };
+        if !is_user_name
+            || (self.human_readable && core::str::from_utf8(&self.value).is_err())
+            || self.name.len() > u16::MAX as usize
+            || self.value.len() > u16::MAX as usize
+        {
+            return Err(PgpError::InvalidNotation);
+        }
+
+        let mut data = Vec::with_capacity(8 + self.name.len() + self.value.len());
+        data.push(if self.human_readable { 0x80 } else { 0 });
+        data.extend(&[0, 0, 0]);
+        data.extend(&bigendian_u16(self.name.len() as u16));
+        data.extend(&bigendian_u16(self.value.len() as u16));
+        data.extend(self.name.as_bytes());
+        data.extend(&self.value);
+        Ok(data)
+    }
+
+    /// Decode a notation from the data of a notation data subpacket.
+    ///
+    /// Names defined by the IETF, without an `@`, are accepted here.
+    pub fn from_subpacket(data: &[u8]) -> Result<Notation, PgpError> {
+        if data.len() < 8 {
+            return Err(PgpError::InvalidNotation);
+        }
+        let human_readable = data[0] & 0x80 != 0;
+        let name_len = BigEndian::read_u16(&data[4..6]) as usize;
+        let value_len = BigEndian::read_u16(&data[6..8]) as usize;
+        if data.len() != 8 + name_len + value_len {
+            return Err(PgpError::InvalidNotation);
+        }
+
+        let name = core::str::from_utf8(&data[8..(8 + name_len)])
+            .map_err(|_| PgpError::InvalidNotation)?;
+        let value = &data[(8 + name_len)..];
+        if human_readable && core::str::from_utf8(value).is_err() {
+            return Err(PgpError::InvalidNotation);
+        }
+
+        Ok(Notation {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            human_readable,
+        })
+    }
+}
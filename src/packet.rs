@@ -1,70 +1,279 @@
-use std::ops::Range;
+//! Low-level helpers for writing OpenPGP packets.
+//!
+//! These are the functions this library uses to frame its own packets.
+//! They are exposed for building packet types the library does not
+//! support, such as literal data packets. They do no validation beyond
+//! what is noted on each function, and the caller is responsible for
+//! producing well-formed OpenPGP data.
+//!
+//! This module is lower level than the rest of the API, and is more likely
+//! to change between releases.
+use alloc::vec::Vec;
+use core::ops::Range;
 
 use byteorder::{BigEndian, ByteOrder};
 
-pub(crate) type BigEndianU32 = [u8; 4];
-pub(crate) type BigEndianU16 = [u8; 2];
+use crate::PgpError;
 
-pub(crate) fn write_packet<F: Fn(&mut Vec<u8>)>(
-    data: &mut Vec<u8>,
-    tag: u8,
-    write: F,
-) -> Range<usize> {
+/// A big endian encoded u32.
+pub type BigEndianU32 = [u8; 4];
+/// A big endian encoded u16.
+pub type BigEndianU16 = [u8; 2];
+
+/// Append a packet with the given tag to the data.
+///
+/// The packet has an old format header with a two octet length. The write
+/// function writes the packet body, and the range of the whole packet
+/// within the data is returned.
+///
+/// # Panics
+///
+/// This panics if the body is longer than 65535 octets, the most a two
+/// octet length can hold.
+pub fn write_packet<F: Fn(&mut Vec<u8>)>(data: &mut Vec<u8>, tag: u8, write: F) -> Range<usize> {
     let init = data.len();
     let header_tag = (tag << 2) | 0b_1000_0001;
     data.extend(&[header_tag, 0, 0]);
     write(data);
     let len = data.len() - init - 3;
-    assert!(len < u16::MAX as usize);
+    assert!(len <= u16::MAX as usize);
     BigEndian::write_u16(&mut data[(init + 1)..(init + 3)], len as u16);
     init..data.len()
 }
 
-pub(crate) fn prepare_packet<F: Fn(&mut Vec<u8>)>(tag: u8, write: F) -> Vec<u8> {
+/// Construct a packet with the given tag.
+///
+/// This is like `write_packet`, except that the packet is returned as a
+/// new buffer. The write function receives the buffer after the three
+/// octet header, so it can read back what it has written from `[3..]`,
+/// but it must not modify the header.
+///
+/// # Panics
+///
+/// This panics if the body is longer than 65535 octets, as `write_packet`
+/// does.
+pub fn prepare_packet<F: FnOnce(&mut Vec<u8>)>(tag: u8, write: F) -> Vec<u8> {
     let mut packet = vec![0, 0, 0];
     write(&mut packet);
     packet[0] = (tag << 2) | 0b_1000_0001;
     let len = packet.len() - 3;
+    assert!(len <= u16::MAX as usize);
     BigEndian::write_u16(&mut packet[1..3], len as u16);
     packet
 }
 
-pub(crate) fn write_subpackets<F>(packet: &mut Vec<u8>, write_each_subpacket: F)
+/// Append a subpacket region to a signature packet.
+///
+/// This writes a two octet length, followed by the subpackets written by
+/// the write function, usually with `write_single_subpacket`.
+///
+/// # Panics
+///
+/// This panics if the region is longer than 65535 octets, the most a two
+/// octet length can hold.
+pub fn write_subpackets<F>(packet: &mut Vec<u8>, write_each_subpacket: F)
 where
-    F: Fn(&mut Vec<u8>),
+    F: FnOnce(&mut Vec<u8>),
 {
     packet.extend(&[0, 0]);
     let init = packet.len();
     write_each_subpacket(packet);
     let len = packet.len() - init;
-    assert!(len < u16::MAX as usize);
+    assert!(len <= u16::MAX as usize);
     BigEndian::write_u16(&mut packet[(init - 2)..init], len as u16);
 }
 
-pub(crate) fn write_single_subpacket<F: Fn(&mut Vec<u8>)>(packet: &mut Vec<u8>, tag: u8, write: F) {
-    packet.extend(&[0, tag]);
-    let init = packet.len() - 1;
+/// Append a subpacket with the given tag to a subpacket region.
+///
+/// The tag is written as given, so it should include the critical bit if
+/// the subpacket is critical. The write function writes the subpacket
+/// data. The length is written in the shortest of the one, two and five
+/// octet forms which can hold it.
+pub fn write_single_subpacket<F: Fn(&mut Vec<u8>)>(packet: &mut Vec<u8>, tag: u8, write: F) {
+    let init = packet.len();
+    packet.push(tag);
     write(packet);
-    let len = packet.len() - init;
-    assert!(len < 191);
-    packet[init - 1] = len as u8;
+    let mut len = Vec::with_capacity(5);
+    write_new_format_length(&mut len, packet.len() - init);
+    packet.splice(init..init, len);
+}
+
+// The length of a subpacket with this much data once written, including
+// its length and tag.
+pub(crate) fn subpacket_len(data_len: usize) -> usize {
+    let len = data_len + 1;
+    match len {
+        0..=191 => 1 + len,
+        192..=8383 => 2 + len,
+        _ => 5 + len,
+    }
+}
+
+/// Get the tag of a packet from the first octet of its header.
+///
+/// Both old and new format headers are supported. Returns
+/// `PgpError::InvalidPacketHeader` if the octet is not a packet header.
+pub fn packet_tag(header: u8) -> Result<u8, PgpError> {
+    if header & 0x80 == 0 {
+        return Err(PgpError::InvalidPacketHeader);
+    }
+    if header & 0x40 != 0 {
+        Ok(header & 0x3f)
+    } else {
+        Ok((header >> 2) & 0x0f)
+    }
+}
+
+// A packet within OpenPGP data.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Packet<'a> {
+    pub tag: u8,
+    // the whole packet, including its header
+    pub bytes: &'a [u8],
+    pub body: &'a [u8],
+}
+
+// Reads a packet with either an old or new format header, returning the
+// packet and the data after it.
+pub(crate) fn read_packet(data: &[u8]) -> Result<(Packet<'_>, &[u8]), PgpError> {
+    let header = *data.first().ok_or(PgpError::InvalidPacketHeader)?;
+    let tag = packet_tag(header)?;
+
+    let (init, len) = if header & 0x40 != 0 {
+        let (len_len, len) = read_new_format_length(&data[1..])?;
+        (1 + len_len, len)
+    } else {
+        match header & 0x03 {
+            0 if data.len() >= 2 => (2, data[1] as usize),
+            1 if data.len() >= 3 => (3, BigEndian::read_u16(&data[1..3]) as usize),
+            2 if data.len() >= 5 => (5, BigEndian::read_u32(&data[1..5]) as usize),
+            3 => return Err(PgpError::UnsupportedPacketLength),
+            _ => return Err(PgpError::InvalidPacketHeader),
+        }
+    };
+
+    if data.len() - init < len {
+        return Err(PgpError::InvalidPacketHeader);
+    }
+
+    let packet = Packet {
+        tag,
+        bytes: &data[..(init + len)],
+        body: &data[init..(init + len)],
+    };
+    Ok((packet, &data[(init + len)..]))
+}
+
+// An iterator over the packets in OpenPGP data. Iteration stops after the
+// first malformed packet.
+#[derive(Debug)]
+pub(crate) struct Packets<'a>(pub &'a [u8]);
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<Packet<'a>, PgpError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        match read_packet(self.0) {
+            Ok((packet, rest)) => {
+                self.0 = rest;
+                Some(Ok(packet))
+            }
+            Err(err) => {
+                self.0 = &[];
+                Some(Err(err))
+            }
+        }
+    }
 }
 
-pub(crate) fn write_mpi(data: &mut Vec<u8>, mpi: &[u8]) {
+// Parses the length of a new format packet, given the data following the
+// tag octet. Returns the number of octets encoding the length, and the
+// length of the packet body.
+pub(crate) fn read_new_format_length(data: &[u8]) -> Result<(usize, usize), PgpError> {
+    match data.first() {
+        Some(&len @ 0..=191) => Ok((1, len as usize)),
+        Some(&first @ 192..=223) => {
+            let second = *data.get(1).ok_or(PgpError::InvalidPacketHeader)?;
+            Ok((2, ((first as usize - 192) << 8) + second as usize + 192))
+        }
+        Some(&(224..=254)) => Err(PgpError::UnsupportedPartialLength),
+        Some(&255) => {
+            if data.len() < 5 {
+                return Err(PgpError::InvalidPacketHeader);
+            }
+            let len = BigEndian::read_u32(&data[1..5]);
+            if len > u16::MAX as u32 {
+                return Err(PgpError::UnsupportedPacketLength);
+            }
+            Ok((5, len as usize))
+        }
+        None => Err(PgpError::InvalidPacketHeader),
+    }
+}
+
+// Writes the length of a new format packet, using the shortest encoding.
+// Subpacket lengths are encoded the same way.
+pub(crate) fn write_new_format_length(data: &mut Vec<u8>, len: usize) {
+    if len < 192 {
+        data.push(len as u8);
+    } else if len < 8384 {
+        let len = len - 192;
+        data.push((len >> 8) as u8 + 192);
+        data.push(len as u8);
+    } else {
+        data.push(255);
+        data.extend(&bigendian_u32(len as u32));
+    }
+}
+
+/// Append a multiprecision integer to the data.
+///
+/// The value is a big endian unsigned integer. Leading zero octets are
+/// removed, and it is prefixed with its length in bits as RFC 4880
+/// requires.
+///
+/// # Panics
+///
+/// This panics if the value is empty, or longer than 8190 octets.
+pub fn write_mpi(data: &mut Vec<u8>, mpi: &[u8]) {
     assert!(mpi.len() < (u16::MAX / 8) as usize);
     assert!(!mpi.is_empty());
-    let len = bigendian_u16((mpi.len() * 8 - (mpi[0].leading_zeros() as usize)) as u16);
-    data.extend(&len);
+    // leading zero bytes are not part of an MPI
+    let zeros = mpi.iter().take_while(|&&byte| byte == 0).count();
+    let mpi = &mpi[zeros..];
+    let bits = match mpi.first() {
+        Some(byte) => mpi.len() * 8 - (byte.leading_zeros() as usize),
+        None => 0,
+    };
+    data.extend(&bigendian_u16(bits as u16));
     data.extend(mpi);
 }
 
-pub(crate) fn bigendian_u32(data: u32) -> BigEndianU32 {
+// Read an MPI, returning its value and the data after it.
+pub(crate) fn read_mpi(data: &[u8]) -> Result<(&[u8], &[u8]), PgpError> {
+    if data.len() < 2 {
+        return Err(PgpError::MalformedMpi);
+    }
+    let bits = BigEndian::read_u16(&data[0..2]) as usize;
+    let len = bits.div_ceil(8);
+    if data.len() < 2 + len {
+        return Err(PgpError::MalformedMpi);
+    }
+    Ok((&data[2..][..len], &data[(2 + len)..]))
+}
+
+/// Encode a u32 as big endian.
+pub fn bigendian_u32(data: u32) -> BigEndianU32 {
     let mut out = BigEndianU32::default();
     BigEndian::write_u32(&mut out, data);
     out
 }
 
-pub(crate) fn bigendian_u16(data: u16) -> BigEndianU16 {
+/// Encode a u16 as big endian.
+pub fn bigendian_u16(data: u16) -> BigEndianU16 {
     let mut out = BigEndianU16::default();
     BigEndian::write_u16(&mut out, data);
     out
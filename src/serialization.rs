@@ -0,0 +1,101 @@
+//! Human-readable formats get the ASCII armored string, binary formats get
+//! the packet bytes.
+use alloc::string::ToString;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{PgpError, PgpKey, PgpSig};
+
+trait Armored: Sized + fmt::Display {
+    const EXPECTING: &'static str;
+    fn as_bytes(&self) -> &[u8];
+    fn from_bytes(bytes: &[u8]) -> Result<Self, PgpError>;
+    fn from_ascii_armor(string: &str) -> Result<Self, PgpError>;
+}
+
+impl Armored for PgpSig {
+    const EXPECTING: &'static str = "an OpenPGP signature";
+    fn as_bytes(&self) -> &[u8] {
+        PgpSig::as_bytes(self)
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self, PgpError> {
+        PgpSig::from_bytes(bytes)
+    }
+    fn from_ascii_armor(string: &str) -> Result<Self, PgpError> {
+        PgpSig::from_ascii_armor(string)
+    }
+}
+
+impl Armored for PgpKey {
+    const EXPECTING: &'static str = "an OpenPGP public key";
+    fn as_bytes(&self) -> &[u8] {
+        PgpKey::as_bytes(self)
+    }
+    fn from_bytes(bytes: &[u8]) -> Result<Self, PgpError> {
+        PgpKey::from_bytes(bytes)
+    }
+    fn from_ascii_armor(string: &str) -> Result<Self, PgpError> {
+        PgpKey::from_ascii_armor(string)
+    }
+}
+
+impl Serialize for PgpSig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PgpSig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl Serialize for PgpKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PgpKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+fn serialize<T: Armored, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&value.to_string())
+    } else {
+        serializer.serialize_bytes(value.as_bytes())
+    }
+}
+
+fn deserialize<'de, T: Armored, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(ArmoredVisitor(PhantomData))
+    } else {
+        deserializer.deserialize_bytes(ArmoredVisitor(PhantomData))
+    }
+}
+
+struct ArmoredVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Armored> Visitor<'de> for ArmoredVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(T::EXPECTING)
+    }
+
+    fn visit_str<E: de::Error>(self, string: &str) -> Result<T, E> {
+        T::from_ascii_armor(string).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<T, E> {
+        T::from_bytes(bytes).map_err(E::custom)
+    }
+}
@@ -0,0 +1,46 @@
+//! SHA-3 hashers for signatures made with SHA3-256 and SHA3-512.
+//!
+//! This library is generic over version 0.7 of the `Digest` trait, which
+//! the releases of the `sha3` crate built on newer versions of `digest` do
+//! not implement. With the `sha3` feature, the types in this module wrap
+//! the hashers of the `sha3` crate and implement the `Digest` trait this
+//! library uses, so they can be passed wherever a hasher is expected, such
+//! as to `PgpSig::new` with `HashAlgorithm::Sha3_256`.
+use digest::generic_array::GenericArray;
+use digest::{BlockInput, FixedOutput, Input};
+use typenum::{U136, U32, U64, U72};
+
+macro_rules! sha3_hasher {
+    ($name:ident, $doc:expr, $block_size:ty, $output_size:ty) => {
+        #[doc = $doc]
+        #[derive(Clone, Default)]
+        pub struct $name(::sha3::$name);
+
+        impl Input for $name {
+            fn process(&mut self, input: &[u8]) {
+                ::sha3::Digest::input(&mut self.0, input);
+            }
+        }
+
+        impl BlockInput for $name {
+            type BlockSize = $block_size;
+        }
+
+        impl FixedOutput for $name {
+            type OutputSize = $output_size;
+
+            fn fixed_result(self) -> GenericArray<u8, $output_size> {
+                GenericArray::clone_from_slice(&::sha3::Digest::result(self.0))
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+    };
+}
+
+sha3_hasher!(Sha3_256, "The SHA3-256 hash function.", U136, U32);
+sha3_hasher!(Sha3_512, "The SHA3-512 hash function.", U72, U64);
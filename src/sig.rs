@@ -1,22 +1,36 @@
-use std::fmt::{self, Debug, Display};
-use std::str::FromStr;
+use alloc::borrow::ToOwned;
+#[cfg(feature = "std")]
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+#[cfg(feature = "std")]
+use core::{fmt::Display, str::FromStr};
+#[cfg(feature = "std")]
+use std::io::{self, Read};
 
 use byteorder::{BigEndian, ByteOrder};
 use digest::Digest;
-use typenum::U32;
+use typenum::Unsigned;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
 #[cfg(feature = "dalek")]
 use dalek::Signer;
 #[cfg(feature = "dalek")]
 use ed25519_dalek as dalek;
 #[cfg(feature = "dalek")]
-use typenum::U64;
+use typenum::{U32, U64};
 
-use crate::ascii_armor::{ascii_armor, remove_ascii_armor};
+#[cfg(feature = "std")]
+use crate::ascii_armor::{ascii_armor, ascii_armor_with_options, remove_ascii_armor, ArmorOptions};
+use crate::key::fingerprints_match;
+#[cfg(feature = "dalek")]
+use crate::key::{fingerprint, write_public_key_packet};
 use crate::packet::*;
 use crate::Base64;
 use crate::PgpError;
-use crate::{Fingerprint, Signature};
+use crate::VerifyBackend;
+use crate::{Fingerprint, KeyId, Notation, SigBuilder, SignError, Signature, VerifyError};
 
 /// The valid types of OpenPGP signatures.
 #[allow(missing_docs)]
@@ -39,229 +53,2198 @@ pub enum SigType {
     ThirdPartyConfirmation = 0x50,
 }
 
+impl SigType {
+    /// The signature type byte used to encode this type in a signature.
+    pub fn as_byte(self) -> u8 {
+        self as u8
+    }
+
+    /// Whether this is a certification of a user id: a generic, persona,
+    /// casual or positive certification.
+    pub fn is_certification(self) -> bool {
+        matches!(
+            self,
+            SigType::GenericCertification
+                | SigType::PersonaCertification
+                | SigType::CasualCertification
+                | SigType::PositiveCertification
+        )
+    }
+
+    /// Whether this is a key, subkey or certification revocation.
+    pub fn is_revocation(self) -> bool {
+        matches!(
+            self,
+            SigType::KeyRevocation | SigType::SubkeyRevocation | SigType::CertificationRevocation
+        )
+    }
+
+    /// Whether this type of signature is made over a key rather than a
+    /// document: a certification, a subkey or primary key binding, a direct
+    /// key signature or a revocation.
+    pub fn certifies_key(self) -> bool {
+        self.is_certification()
+            || self.is_revocation()
+            || matches!(
+                self,
+                SigType::SubkeyBinding | SigType::PrimaryKeyBinding | SigType::DirectlyOnKey
+            )
+    }
+}
+
+impl TryFrom<u8> for SigType {
+    type Error = PgpError;
+
+    fn try_from(byte: u8) -> Result<SigType, PgpError> {
+        Ok(match byte {
+            0x00 => SigType::BinaryDocument,
+            0x01 => SigType::TextDocument,
+            0x02 => SigType::Standalone,
+            0x10 => SigType::GenericCertification,
+            0x11 => SigType::PersonaCertification,
+            0x12 => SigType::CasualCertification,
+            0x13 => SigType::PositiveCertification,
+            0x18 => SigType::SubkeyBinding,
+            0x19 => SigType::PrimaryKeyBinding,
+            0x1F => SigType::DirectlyOnKey,
+            0x20 => SigType::KeyRevocation,
+            0x28 => SigType::SubkeyRevocation,
+            0x30 => SigType::CertificationRevocation,
+            0x40 => SigType::Timestamp,
+            0x50 => SigType::ThirdPartyConfirmation,
+            _ => return Err(PgpError::UnknownSignatureType(byte)),
+        })
+    }
+}
+
+impl fmt::Display for SigType {
+    /// Writes the name of the signature type and its type byte, as gpg
+    /// does, such as `Positive certification (0x13)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SigType::BinaryDocument => "Binary document",
+            SigType::TextDocument => "Text document",
+            SigType::Standalone => "Standalone",
+            SigType::GenericCertification => "Generic certification",
+            SigType::PersonaCertification => "Persona certification",
+            SigType::CasualCertification => "Casual certification",
+            SigType::PositiveCertification => "Positive certification",
+            SigType::SubkeyBinding => "Subkey binding",
+            SigType::PrimaryKeyBinding => "Primary key binding",
+            SigType::DirectlyOnKey => "Direct key",
+            SigType::KeyRevocation => "Key revocation",
+            SigType::SubkeyRevocation => "Subkey revocation",
+            SigType::CertificationRevocation => "Certification revocation",
+            SigType::Timestamp => "Timestamp",
+            SigType::ThirdPartyConfirmation => "Third-party confirmation",
+        };
+        write!(f, "{} ({:#04x})", name, self.as_byte())
+    }
+}
+
+/// The reason given in a revocation signature for revoking a key.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RevocationReason {
+    /// No reason was specified.
+    NoReason = 0,
+    /// The key has been replaced by a new key.
+    KeySuperseded = 1,
+    /// The private key material may have been compromised.
+    KeyCompromised = 2,
+    /// The key is no longer used.
+    KeyRetired = 3,
+    /// The user id is no longer valid.
+    UserIdInvalid = 32,
+}
+
+impl RevocationReason {
+    /// The code used to encode this reason in a reason for revocation
+    /// subpacket.
+    pub fn as_byte(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for RevocationReason {
+    type Error = PgpError;
+
+    fn try_from(byte: u8) -> Result<RevocationReason, PgpError> {
+        Ok(match byte {
+            0 => RevocationReason::NoReason,
+            1 => RevocationReason::KeySuperseded,
+            2 => RevocationReason::KeyCompromised,
+            3 => RevocationReason::KeyRetired,
+            32 => RevocationReason::UserIdInvalid,
+            _ => return Err(PgpError::UnknownRevocationReason(byte)),
+        })
+    }
+}
+
+/// The hash algorithms which can be used to produce a signature.
+///
+/// The discriminants are the hash algorithm IDs assigned by RFC 4880 and
+/// its successors. This library does not implement any of them; the
+/// digest is supplied by the caller, such as `sha2::Sha512` or a SHA-3
+/// implementation of the `Digest` trait.
+///
+/// MD5 (1), SHA-1 (2) and RIPEMD-160 (3) are broken or too weak to trust.
+/// Signatures made with them can be parsed, but by default they are not
+/// verified: verification only accepts `PgpSig::ALLOWED_HASH_ALGORITHMS`,
+/// and fails with `PgpError::WeakHashAlgorithm` for the weak ones. Use
+/// `Verifier::allow_hash_algorithms` to choose the accepted algorithms.
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum HashAlgorithm {
+    Md5 = 1,
+    Sha1 = 2,
+    Ripemd160 = 3,
+    Sha256 = 8,
+    Sha384 = 9,
+    Sha512 = 10,
+    Sha224 = 11,
+    Sha3_256 = 12,
+    Sha3_512 = 14,
+}
+
+impl HashAlgorithm {
+    /// The hash algorithm ID used to encode this algorithm in a signature.
+    pub fn as_byte(self) -> u8 {
+        self as u8
+    }
+
+    /// The length in bytes of a digest produced by this algorithm.
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Md5 => 16,
+            HashAlgorithm::Sha1 | HashAlgorithm::Ripemd160 => 20,
+            HashAlgorithm::Sha224 => 28,
+            HashAlgorithm::Sha256 | HashAlgorithm::Sha3_256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 | HashAlgorithm::Sha3_512 => 64,
+        }
+    }
+
+    /// Whether this is MD5, SHA-1 or RIPEMD-160, which are too weak to
+    /// trust.
+    pub fn is_weak(self) -> bool {
+        matches!(
+            self,
+            HashAlgorithm::Md5 | HashAlgorithm::Sha1 | HashAlgorithm::Ripemd160
+        )
+    }
+}
+
+impl TryFrom<u8> for HashAlgorithm {
+    type Error = PgpError;
+
+    fn try_from(byte: u8) -> Result<HashAlgorithm, PgpError> {
+        Ok(match byte {
+            1 => HashAlgorithm::Md5,
+            2 => HashAlgorithm::Sha1,
+            3 => HashAlgorithm::Ripemd160,
+            8 => HashAlgorithm::Sha256,
+            9 => HashAlgorithm::Sha384,
+            10 => HashAlgorithm::Sha512,
+            11 => HashAlgorithm::Sha224,
+            12 => HashAlgorithm::Sha3_256,
+            14 => HashAlgorithm::Sha3_512,
+            _ => return Err(PgpError::UnsupportedHashAlgorithm(byte)),
+        })
+    }
+}
+
 /// A subpacket to be hashed into the signed data.
 ///
 /// See RFC 4880 for more information.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
 pub struct SubPacket<'a> {
-    /// The tag for this subpacket.
+    /// The tag for this subpacket, without the critical bit.
     pub tag: u8,
     /// The data in this subpacket.
     pub data: &'a [u8],
+    /// Whether this subpacket is critical.
+    ///
+    /// An implementation which does not recognize a critical subpacket must
+    /// treat the signature as invalid. This is encoded as the high bit of
+    /// the tag.
+    pub critical: bool,
+}
+
+impl<'a> SubPacket<'a> {
+    /// The tag of trust signature subpackets.
+    pub const TRUST_SIGNATURE: u8 = 5;
+    /// The tag of regular expression subpackets.
+    pub const REGULAR_EXPRESSION: u8 = 6;
+
+    /// Encode the data of a trust signature subpacket.
+    ///
+    /// A certification with this subpacket delegates trust to the
+    /// certified key. The level is how many further delegations are
+    /// trusted, where 0 is an ordinary certification and 1 makes the key a
+    /// trusted introducer. The amount is how far the key is trusted, where
+    /// 60 is partial trust and 120 is complete trust.
+    pub fn trust_signature_data(level: u8, amount: u8) -> [u8; 2] {
+        [level, amount]
+    }
+
+    /// Encode the data of a regular expression subpacket.
+    ///
+    /// In a trust signature, this limits the trust delegated to the
+    /// certified key to user ids which match the regular expression. The
+    /// expression is written with the null terminator RFC 4880 requires.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the expression contains a null byte.
+    pub fn regular_expression_data(regex: &str) -> Vec<u8> {
+        assert!(
+            !regex.contains('\0'),
+            "regular expression contains a null byte"
+        );
+        let mut data = Vec::with_capacity(regex.len() + 1);
+        data.extend(regex.as_bytes());
+        data.push(0);
+        data
+    }
+}
+
+// Iterates over the subpackets in a length-prefixed subpacket region,
+// stopping at the first subpacket which is truncated or malformed.
+pub(crate) struct SubPacketIter<'a> {
+    pub(crate) data: &'a [u8],
+}
+
+impl<'a> Iterator for SubPacketIter<'a> {
+    type Item = SubPacket<'a>;
+
+    fn next(&mut self) -> Option<SubPacket<'a>> {
+        let (init, len) = match *self.data.first()? {
+            len @ 0..=191 => (1, len as usize),
+            first @ 192..=254 => {
+                let second = *self.data.get(1)? as usize;
+                (2, ((first as usize - 192) << 8) + second + 192)
+            }
+            255 => {
+                if self.data.len() < 5 {
+                    self.data = &[];
+                    return None;
+                }
+                (5, BigEndian::read_u32(&self.data[1..5]) as usize)
+            }
+        };
+
+        // the length includes the tag, so it must be at least one
+        if len == 0 || self.data.len() - init < len {
+            self.data = &[];
+            return None;
+        }
+
+        let tag = self.data[init];
+        let data = &self.data[(init + 1)..(init + len)];
+        self.data = &self.data[(init + len)..];
+        Some(SubPacket {
+            tag: tag & 0x7f,
+            data,
+            critical: tag & 0x80 != 0,
+        })
+    }
 }
 
 /// An OpenPGP formatted ed25519 signature.
-#[derive(Eq, PartialEq, Hash)]
+///
+/// Equality, hashing and ordering are over the binary representation, as
+/// returned by `as_bytes`. Parsing normalizes the packet header, so a
+/// signature is equal to itself after a round trip through bytes, but two
+/// signatures which differ only in their unhashed subpackets are not
+/// equal. Use `canonical_eq` to compare signatures while ignoring the
+/// unhashed subpackets, or key a map by `without_unhashed` to deduplicate
+/// them.
+///
+/// A signature can also be compared to bytes, which are equal if they are
+/// `as_bytes`, and to a string, which is equal if `from_ascii_armor`
+/// parses it to the same bytes. A string which does not parse is not
+/// equal to any signature.
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct PgpSig {
+    // A signature packet with a three byte header. Every PgpSig is either
+    // built by this library or has passed has_correct_structure and, unless
+    // parsed by from_bytes_legacy, has_correct_hashed_subpackets, so the
+    // accessors below index into it without further bounds checks.
     data: Vec<u8>,
 }
 
 impl PgpSig {
+    /// The hash algorithms accepted when verifying a signature, unless the
+    /// `Verifier` is given others: every supported algorithm except MD5,
+    /// SHA-1 and RIPEMD-160.
+    pub const ALLOWED_HASH_ALGORITHMS: &'static [HashAlgorithm] = &[
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha384,
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Sha224,
+        HashAlgorithm::Sha3_256,
+        HashAlgorithm::Sha3_512,
+    ];
+
+    /// The tags of the subpackets this library recognizes: signature
+    /// creation and expiration time, key expiration time, algorithm
+    /// preferences, issuer, notation data, keyserver preferences, preferred
+    /// key server, primary user id, key flags, reason for revocation,
+    /// embedded signature and issuer fingerprint.
+    pub const KNOWN_SUBPACKETS: &'static [u8] =
+        &[2, 3, 9, 11, 16, 20, 21, 22, 23, 24, 25, 27, 29, 32, 33];
+
+    /// The name of the notation which binds a signature to an application
+    /// context. See `new_with_context`.
+    pub const CONTEXT_NOTATION: &'static str = "context@pkgx.dev";
+
     /// Construct a new PGP signature.
     ///
     /// This will construct a valid OpenPGP signature using the ed25519
-    /// signing algorithm & SHA-256 hashing algorithm. It will contain
+    /// signing algorithm & the given hashing algorithm. It will contain
     /// these hashed subpackets:
     ///  - A version 4 key fingerprint
     ///  - A timestamp
+    ///  - An expiration time, if `expiration` is `Some`; this is the
+    ///    number of seconds after `unix_time` at which the signature expires
     ///  - Whatever subpackets you pass as arguments
     ///
     /// It will contain the key id as an unhashed subpacket.
-    pub fn new<Sha256, F>(
+    ///
+    /// The signature is a function of these arguments alone, so with a
+    /// deterministic sign function such as ed25519, signing again with the
+    /// same `unix_time` produces the same bytes. For reproducible builds,
+    /// pass a fixed time such as `source_date_epoch` rather than the
+    /// current time.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of the hash algorithm.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<D, F>(
         data: &[u8],
         fingerprint: Fingerprint,
         sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
         unix_time: u32,
+        expiration: Option<u32>,
         subpackets: &[SubPacket],
         sign: F,
     ) -> PgpSig
     where
-        Sha256: Digest<OutputSize = U32>,
+        D: Digest,
         F: Fn(&[u8]) -> Signature,
     {
-        let data = prepare_packet(2, |packet| {
-            packet.push(4); // version number
-            packet.push(sig_type as u8); // signature class
-            packet.push(22); // signing algorithm (EdDSA)
-            packet.push(8); // hash algorithm (SHA-256)
-
-            write_subpackets(packet, |hashed_subpackets| {
-                // fingerprint
-                write_single_subpacket(hashed_subpackets, 33, |packet| {
-                    packet.push(4);
-                    packet.extend(&fingerprint);
+        PgpSig::new_with_input::<D, _, _>(
+            |hasher| hasher.process(data),
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+            sign,
+        )
+    }
+
+    /// Start building a new PGP signature.
+    ///
+    /// See `SigBuilder`.
+    pub fn builder<'a>() -> SigBuilder<'a> {
+        SigBuilder::new()
+    }
+
+    /// Construct a new PGP signature with a sign function which may fail.
+    ///
+    /// This is the same as `new`, except that the sign function returns a
+    /// `Result`, as a call to a remote signing service or HSM might. If it
+    /// returns an error, that error is returned. The digest is computed
+    /// before the sign function is called.
+    ///
+    /// Rather than panicking if the subpackets are too large, this returns
+    /// `SignError::Packet(PgpError::SubpacketTooLarge)` without calling the
+    /// sign function.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<D, F, E>(
+        data: &[u8],
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> Result<PgpSig, SignError<E>>
+    where
+        D: Digest,
+        F: FnOnce(&[u8]) -> Result<Signature, E>,
+    {
+        PgpSig::check_subpacket_sizes(subpackets).map_err(SignError::Packet)?;
+        PgpSig::try_new_with_input::<D, _, _, _>(
+            |hasher| hasher.process(data),
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+            sign,
+        )
+        .map_err(SignError::Signer)
+    }
+
+    /// Construct a new PGP text document signature.
+    ///
+    /// This is like `new` with a signature type of `TextDocument`, but the
+    /// text is canonicalized before it is hashed, as RFC 4880 requires:
+    /// every line ending (CRLF, LF or CR) is converted to CRLF, and spaces
+    /// and tabs at the end of each line are removed. Signatures made this
+    /// way should be checked with `verify_text`.
+    pub fn new_text<D, F>(
+        text: &[u8],
+        fingerprint: Fingerprint,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        PgpSig::new_with_input::<D, _, _>(
+            |hasher| hash_canonical_text(hasher, text),
+            fingerprint,
+            SigType::TextDocument,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+            sign,
+        )
+    }
+
+    /// Construct a new PGP signature bound to an application context.
+    ///
+    /// This is like `new`, with a hashed notation named
+    /// `PgpSig::CONTEXT_NOTATION` whose value is the context, added before
+    /// the other subpackets. Signatures made this way should be checked
+    /// with `verify_with_context`, so that a signature made for one purpose
+    /// cannot be presented as one made for another.
+    ///
+    /// The notation is not critical, so other OpenPGP implementations can
+    /// still verify the signature, ignoring the context.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the context is longer than 65535 bytes, or in
+    /// the cases that `new` does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_context<D, F>(
+        context: &str,
+        data: &[u8],
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let notation = Notation::text(PgpSig::CONTEXT_NOTATION, context)
+            .to_subpacket()
+            .expect("context is too long for a notation");
+        let mut all_subpackets = Vec::with_capacity(subpackets.len() + 1);
+        all_subpackets.push(SubPacket {
+            tag: Notation::TAG,
+            data: &notation,
+            critical: false,
+        });
+        all_subpackets.extend(subpackets);
+        PgpSig::new::<D, _>(
+            data,
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            &all_subpackets,
+            sign,
+        )
+    }
+
+    /// Construct a new signature over the data with the same options as
+    /// this one, but made by another key at another time.
+    ///
+    /// The new signature has this signature's type, hash algorithm,
+    /// expiration and hashed subpackets, except that the issuer
+    /// fingerprint, creation time and issuer subpackets are replaced with
+    /// ones for the new fingerprint and timestamp. As with `SigBuilder`,
+    /// text document signatures are made over canonicalized text.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of this signature's hash algorithm, or if this
+    /// signature's type is not recognized.
+    pub fn resign<D, F>(
+        &self,
+        data: &[u8],
+        fingerprint: Fingerprint,
+        unix_time: u32,
+        sign: F,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        let mut builder = PgpSig::builder()
+            .sig_type(self.sig_type())
+            .hash_algorithm(self.hash_algorithm())
+            .timestamp(unix_time);
+        if let Some(expiration) = self.expiration() {
+            builder = builder.expiration(expiration);
+        }
+        for subpacket in self.hashed_subpackets() {
+            if !matches!(subpacket.tag, 2 | 3 | 16 | 33) {
+                builder = builder.subpacket(subpacket);
+            }
+        }
+        builder.sign::<D, _>(data, fingerprint, sign)
+    }
+
+    /// Construct a new standalone PGP signature.
+    ///
+    /// A standalone signature is made over its own subpackets only, with no
+    /// document data. Otherwise, this is the same as `new`. Signatures made
+    /// this way should be checked with `verify_standalone`.
+    pub fn new_standalone<D, F>(
+        fingerprint: Fingerprint,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        PgpSig::new_with_input::<D, _, _>(
+            |_| {},
+            fingerprint,
+            SigType::Standalone,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+            sign,
+        )
+    }
+
+    /// Construct a new PGP timestamp signature.
+    ///
+    /// This is like `new_standalone`, but with a signature type of
+    /// `Timestamp`: the signature attests only to its creation time, and
+    /// whatever subpackets you pass.
+    pub fn new_timestamp<D, F>(
+        fingerprint: Fingerprint,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> Signature,
+    {
+        PgpSig::new_with_input::<D, _, _>(
+            |_| {},
+            fingerprint,
+            SigType::Timestamp,
+            hash_algorithm,
+            unix_time,
+            None,
+            subpackets,
+            sign,
+        )
+    }
+
+    /// Construct a new PGP signature over data which is hashed by the
+    /// caller.
+    ///
+    /// The input function hashes the data to be signed into the hasher, as
+    /// with `verify`, and the sign function receives only the final digest.
+    /// This suits signers which never see the signed data, such as HSMs or
+    /// remote signing services. The signature has the same subpackets as
+    /// one made by `new`, with no expiration or extra subpackets.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of the hash algorithm.
+    pub fn from_prehashed<D, F1, F2>(
+        input: F1,
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        sign: F2,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8]) -> Signature,
+    {
+        PgpSig::new_with_input::<D, _, _>(
+            input,
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            None,
+            &[],
+            sign,
+        )
+    }
+
+    // Like new, but the signed data is hashed into the hasher by the input
+    // function rather than passed as a slice.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new_with_input<D, F1, F2>(
+        input: F1,
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F2,
+    ) -> PgpSig
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8]) -> Signature,
+    {
+        let unsigned = PgpSig::prepare_with_input::<D, _>(
+            input,
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+        );
+        let signature = sign(unsigned.digest());
+        unsigned.finalize(signature)
+    }
+
+    // Like new_with_input, but the sign function may fail.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn try_new_with_input<D, F1, F2, E>(
+        input: F1,
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+        sign: F2,
+    ) -> Result<PgpSig, E>
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8]) -> Result<Signature, E>,
+    {
+        let unsigned = PgpSig::prepare_with_input::<D, _>(
+            input,
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+        );
+        let signature = sign(unsigned.digest())?;
+        Ok(unsigned.finalize(signature))
+    }
+
+    /// Prepare a new PGP signature, to be signed separately.
+    ///
+    /// This takes the same arguments as `new`, except for the sign
+    /// function. The returned `UnsignedSig` holds the digest to be signed
+    /// with ed25519; passing the signature to its `finalize` method
+    /// produces the same signature `new` would.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of the hash algorithm.
+    pub fn prepare<D: Digest>(
+        data: &[u8],
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+    ) -> UnsignedSig {
+        PgpSig::prepare_with_input::<D, _>(
+            |hasher| hasher.process(data),
+            fingerprint,
+            sig_type,
+            hash_algorithm,
+            unix_time,
+            expiration,
+            subpackets,
+        )
+    }
+
+    // Like prepare, but the signed data is hashed into the hasher by the
+    // input function rather than passed as a slice.
+    pub(crate) fn prepare_with_input<D, F>(
+        input: F,
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        expiration: Option<u32>,
+        subpackets: &[SubPacket],
+    ) -> UnsignedSig
+    where
+        D: Digest,
+        F: FnOnce(&mut D),
+    {
+        assert!(D::OutputSize::to_usize() == hash_algorithm.digest_len());
+        assert!(
+            PgpSig::check_subpacket_sizes(subpackets).is_ok(),
+            "subpackets are too large to fit in a signature packet"
+        );
+
+        let mut body = vec![
+            4,                        // version number
+            sig_type.as_byte(),       // signature class
+            22,                       // signing algorithm (EdDSA)
+            hash_algorithm.as_byte(), // hash algorithm
+        ];
+
+        write_subpackets(&mut body, |hashed_subpackets| {
+            // fingerprint
+            write_single_subpacket(hashed_subpackets, 33, |packet| {
+                packet.push(4);
+                packet.extend(&fingerprint[..]);
+            });
+
+            // timestamp
+            write_single_subpacket(hashed_subpackets, 2, |packet| {
+                packet.extend(&bigendian_u32(unix_time))
+            });
+
+            // expiration time
+            if let Some(expiration) = expiration {
+                write_single_subpacket(hashed_subpackets, 3, |packet| {
+                    packet.extend(&bigendian_u32(expiration))
                 });
+            }
 
-                // timestamp
-                write_single_subpacket(hashed_subpackets, 2, |packet| {
-                    packet.extend(&bigendian_u32(unix_time))
+            for subpacket in subpackets {
+                let tag = if subpacket.critical {
+                    subpacket.tag | 0x80
+                } else {
+                    subpacket.tag
+                };
+                write_single_subpacket(hashed_subpackets, tag, |packet| {
+                    packet.extend(subpacket.data)
                 });
+            }
+        });
+
+        let hash = {
+            let mut hasher = D::default();
+
+            input(&mut hasher);
+
+            hasher.process(&body);
+
+            hasher.process(&[0x04, 0xff]);
+            hasher.process(&bigendian_u32(body.len() as u32));
+
+            hasher.fixed_result()
+        };
+
+        write_subpackets(&mut body, |unhashed_subpackets| {
+            write_single_subpacket(unhashed_subpackets, 16, |packet| {
+                packet.extend(&fingerprint[12..]);
+            });
+            write_single_subpacket(unhashed_subpackets, 33, |packet| {
+                packet.push(4);
+                packet.extend(&fingerprint[..]);
+            });
+        });
+
+        body.extend(&hash[0..2]);
+
+        UnsignedSig {
+            body,
+            digest: hash.to_vec(),
+        }
+    }
+
+    /// Check that a signature with these hashed subpackets can be encoded.
+    ///
+    /// A signature packet, and the hashed subpackets within it, must each
+    /// be shorter than 65536 octets. This returns
+    /// `PgpError::SubpacketTooLarge` if the subpackets, together with the
+    /// subpackets every signature has and an expiration time, would not
+    /// fit. Constructors such as `new` panic in that case, while `try_new`
+    /// returns the error.
+    pub fn check_subpacket_sizes(subpackets: &[SubPacket]) -> Result<(), PgpError> {
+        // the fingerprint, timestamp and expiration subpackets
+        let hashed_len = subpackets
+            .iter()
+            .map(|subpacket| subpacket_len(subpacket.data.len()))
+            .fold(23 + 6 + 6, usize::saturating_add);
+        // the version, type and algorithms, the two subpacket lengths, the
+        // issuer and fingerprint unhashed subpackets, the hash prefix, and
+        // two MPIs of at most 34 octets
+        let body_len = hashed_len.saturating_add(4 + 2 + 2 + 10 + 23 + 2 + 68);
+        if body_len > u16::MAX as usize {
+            return Err(PgpError::SubpacketTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Parse an OpenPGP signature from binary data.
+    ///
+    /// This must be an ed25519 signature using a supported hash algorithm,
+    /// and it must be in the subset of OpenPGP supported by this library.
+    ///
+    /// The data must contain exactly one signature packet; any data after
+    /// the packet is an error.
+    ///
+    /// Packet headers are normalized to the old format with a two octet
+    /// length, so `as_bytes` returns the input unchanged only if it already
+    /// had that header (`0x89`). See `from_bytes_exact`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PgpSig, PgpError> {
+        let (sig, rest) = PgpSig::from_bytes_lenient(bytes)?;
+        if !rest.is_empty() {
+            return Err(PgpError::TrailingData);
+        }
+        Ok(sig)
+    }
+
+    #[cfg(feature = "std")]
+    /// Read an OpenPGP signature packet from a reader.
+    ///
+    /// The packet header is read first, and then exactly as many bytes as
+    /// it gives as the length of the packet, so the reader is left just
+    /// after the packet. This lets a stream of packets be read one at a
+    /// time. The packet is parsed as in `from_bytes`.
+    ///
+    /// If the reader ends before the end of the packet, this returns
+    /// `PgpError::UnexpectedEof`, including when it ends before the first
+    /// byte.
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<PgpSig, PgpError> {
+        let mut data = read_signature_header_from(reader)?;
+        let (init, len) = read_signature_header(&data)?;
+        data.resize(init + len, 0);
+        read_exact(reader, &mut data[init..])?;
+        PgpSig::from_bytes(&data)
+    }
+
+    /// Parse an OpenPGP signature from binary data, requiring that
+    /// `as_bytes` reproduces the data exactly.
+    ///
+    /// This is like `from_bytes`, but rather than normalizing the packet
+    /// header, it returns an error if the header is not already in the
+    /// normalized form. This is useful when signatures are stored or
+    /// identified by their hash.
+    pub fn from_bytes_exact(bytes: &[u8]) -> Result<PgpSig, PgpError> {
+        let sig = PgpSig::from_bytes(bytes)?;
+        if sig.data != bytes {
+            return Err(PgpError::NonCanonicalHeader);
+        }
+        Ok(sig)
+    }
+
+    /// Parse an OpenPGP signature from binary data without copying it.
+    ///
+    /// This is like `from_bytes`, but the returned `PgpSigRef` borrows the
+    /// data rather than allocating, which suits verifiers with little
+    /// memory. Because the packet header cannot be normalized in place, it
+    /// must already be in the normalized form (`0x89`), or this returns
+    /// `PgpError::NonCanonicalHeader`.
+    pub fn from_bytes_borrowed(bytes: &[u8]) -> Result<PgpSigRef<'_>, PgpError> {
+        let (init, len) = read_signature_header(bytes)?;
+        if bytes[0] != 0x89 {
+            return Err(PgpError::NonCanonicalHeader);
+        }
+        if bytes.len() < init + len {
+            return Err(PgpError::InvalidPacketHeader);
+        }
+        if bytes.len() > init + len {
+            return Err(PgpError::TrailingData);
+        }
+        has_correct_structure(&bytes[3..])?;
+        has_correct_hashed_subpackets(&bytes[3..])?;
+        let sig = PgpSigRef { data: bytes };
+        has_matching_fingerprints(sig)?;
+        Ok(sig)
+    }
+
+    /// Borrow this signature as a `PgpSigRef`.
+    pub fn as_borrowed(&self) -> PgpSigRef<'_> {
+        PgpSigRef { data: &self.data }
+    }
+
+    /// Parse an OpenPGP signature from binary data, accepting signatures
+    /// without an issuer fingerprint subpacket.
+    ///
+    /// Signatures made by older versions of gpg have no fingerprint
+    /// subpacket, and identify their key only by an issuer key ID subpacket
+    /// in the unhashed section. This is like `from_bytes`, but accepts such
+    /// signatures as long as they have an issuer key ID. For them,
+    /// `fingerprint` has only the key ID, and `has_fingerprint` is false.
+    ///
+    /// The key ID is not covered by the signature, so it is only a hint of
+    /// which key to verify with. Prefer `from_bytes` unless you need to
+    /// verify archived signatures.
+    pub fn from_bytes_legacy(bytes: &[u8]) -> Result<PgpSig, PgpError> {
+        let (data, rest) = find_signature_packet(bytes)?;
+        if !rest.is_empty() {
+            return Err(PgpError::TrailingData);
+        }
+        has_correct_structure(&data[3..])?;
+        let sig = PgpSig { data };
+        if !sig.has_fingerprint() {
+            if sig.issuer_key_id().is_none() {
+                return Err(PgpError::MissingFingerprintSubpacket);
+            }
+            return Ok(sig);
+        }
+        has_correct_hashed_subpackets(&sig.data[3..])?;
+        has_matching_fingerprints(sig.as_borrowed())?;
+        Ok(sig)
+    }
+
+    /// Parse the first OpenPGP signature packet in the data.
+    ///
+    /// Unlike `from_bytes`, data after the signature packet is allowed, and
+    /// is returned alongside the signature.
+    pub fn from_bytes_lenient(bytes: &[u8]) -> Result<(PgpSig, &[u8]), PgpError> {
+        let (data, rest) = find_signature_packet(bytes)?;
+        has_correct_structure(&data[3..])?;
+        has_correct_hashed_subpackets(&data[3..])?;
+        let sig = PgpSig { data };
+        has_matching_fingerprints(sig.as_borrowed())?;
+        Ok((sig, rest))
+    }
+
+    #[cfg(feature = "std")]
+    /// Parse an OpenPGP signature from ASCII armored data.
+    ///
+    /// If the armor has a checksum line, it must match the data, but armor
+    /// without a checksum line is accepted.
+    ///
+    /// Any other kind of armored block, such as a public key, is
+    /// `InvalidAsciiArmor`; `ascii_armor::peek_armor_label` tells which kind
+    /// of block it was.
+    pub fn from_ascii_armor(string: &str) -> Result<PgpSig, PgpError> {
+        let data = remove_ascii_armor(string, "BEGIN PGP SIGNATURE", "END PGP SIGNATURE", false)?;
+        PgpSig::from_bytes(&data)
+    }
+
+    #[cfg(feature = "std")]
+    /// Parse an OpenPGP signature from ASCII armored data, requiring the
+    /// armor to have a valid checksum line.
+    pub fn from_ascii_armor_strict(string: &str) -> Result<PgpSig, PgpError> {
+        let data = remove_ascii_armor(string, "BEGIN PGP SIGNATURE", "END PGP SIGNATURE", true)?;
+        PgpSig::from_bytes(&data)
+    }
+
+    #[cfg(feature = "std")]
+    /// ASCII armor this signature, with the given armor header lines.
+    ///
+    /// Each pair is written as a `key: value` line, such as
+    /// `("Comment", "signed by pkgx")`, between the armor's BEGIN line and
+    /// the armored data.
+    pub fn to_armored_string_with_headers(&self, headers: &[(&str, &str)]) -> String {
+        let mut string = String::new();
+        ascii_armor(
+            "BEGIN PGP SIGNATURE",
+            "END PGP SIGNATURE",
+            headers,
+            &self.data[..],
+            &mut string,
+        )
+        .expect("writing to a String cannot fail");
+        string
+    }
+
+    #[cfg(feature = "std")]
+    /// ASCII armor this signature, wrapping the base64 data at the given
+    /// number of characters.
+    ///
+    /// `Display` wraps at 64 characters, as GnuPG does; some consumers
+    /// require another width, such as 76. Parsing accepts any width.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `width` is zero.
+    pub fn to_armored_string_with_width(&self, width: usize) -> String {
+        self.to_armored_string_with_options(&ArmorOptions {
+            line_width: width,
+            ..ArmorOptions::default()
+        })
+    }
+
+    #[cfg(feature = "std")]
+    /// ASCII armor this signature with the given options.
+    ///
+    /// This can write armor headers, wrap the base64 data at any width, and
+    /// omit the checksum line, which RFC 9580 deprecates. Parsing with
+    /// `from_ascii_armor` accepts armor without a checksum line, but
+    /// `from_ascii_armor_strict` does not.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `options.line_width` is zero.
+    pub fn to_armored_string_with_options(&self, options: &ArmorOptions) -> String {
+        let mut string = String::new();
+        ascii_armor_with_options(
+            "BEGIN PGP SIGNATURE",
+            "END PGP SIGNATURE",
+            &self.data[..],
+            options,
+            &mut string,
+        )
+        .expect("writing to a String cannot fail");
+        string
+    }
+
+    #[cfg(feature = "std")]
+    /// ASCII armor this signature in a canonical form.
+    ///
+    /// The armor has no header lines, wraps the base64 data at 64
+    /// characters, uses LF line endings and always has a checksum line.
+    /// It depends only on `as_bytes`, so armors which differ only in their
+    /// formatting produce the same canonical armor.
+    pub fn to_canonical_armor(&self) -> String {
+        self.to_armored_string_with_options(&ArmorOptions::default())
+    }
+
+    /// Get the binary representation of this signature.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the binary representation of this signature, using a new format
+    /// packet header.
+    ///
+    /// `as_bytes` always uses an old format header; some implementations
+    /// only accept new format headers.
+    pub fn to_bytes_new_format(&self) -> Vec<u8> {
+        let body = &self.data[3..];
+        let mut data = Vec::with_capacity(body.len() + 6);
+        data.push(0xc2);
+        write_new_format_length(&mut data, body.len());
+        data.extend(body);
+        data
+    }
+
+    /// A copy of this signature with no unhashed subpackets.
+    ///
+    /// Unhashed subpackets, such as the issuer key ID, are not covered by
+    /// the signature, and different implementations write different ones.
+    /// Removing them leaves the hashed section and the signature itself
+    /// unchanged, so the copy still verifies, and signatures which differ
+    /// only in their unhashed subpackets become byte-identical.
+    ///
+    /// For a signature without a fingerprint subpacket, this removes the
+    /// issuer key ID, so `fingerprint` of the copy is `Fingerprint::ZERO`.
+    pub fn without_unhashed(&self) -> PgpSig {
+        let packet = &self.data[3..];
+        let hashed_end = BigEndian::read_u16(&packet[4..6]) as usize + 6;
+        let unhashed_len = BigEndian::read_u16(&packet[hashed_end..][..2]) as usize;
+        let data = prepare_packet(2, |data| {
+            data.extend(&packet[..hashed_end]);
+            data.extend(&[0, 0]);
+            data.extend(&packet[(hashed_end + 2 + unhashed_len)..]);
+        });
+        PgpSig { data }
+    }
+
+    /// A copy of this signature with the unhashed subpackets of both this
+    /// and another signature.
+    ///
+    /// The signatures must be the same signature, as in `canonical_eq`,
+    /// differing only in their unhashed subpackets, or this returns
+    /// `PgpError::SignatureMismatch`. The copy has this signature's
+    /// unhashed subpackets, followed by those of the other signature which
+    /// this signature does not have. This combines metadata, such as issuer
+    /// key IDs, which different sources attached to the same signature.
+    ///
+    /// Returns `PgpError::SubpacketTooLarge` if the merged subpackets do not
+    /// fit in a signature packet.
+    pub fn with_merged_unhashed(&self, other: &PgpSig) -> Result<PgpSig, PgpError> {
+        if !self.canonical_eq(other) {
+            return Err(PgpError::SignatureMismatch);
+        }
+
+        let mut subpackets: Vec<SubPacket> = self.unhashed_subpackets().collect();
+        for subpacket in other.unhashed_subpackets() {
+            if !subpackets.contains(&subpacket) {
+                subpackets.push(subpacket);
+            }
+        }
+
+        let packet = &self.data[3..];
+        let hashed_end = BigEndian::read_u16(&packet[4..6]) as usize + 6;
+        let unhashed_len = BigEndian::read_u16(&packet[hashed_end..][..2]) as usize;
+        let rest = &packet[(hashed_end + 2 + unhashed_len)..];
+        let merged_len = subpackets
+            .iter()
+            .map(|subpacket| subpacket_len(subpacket.data.len()))
+            .fold(0, usize::saturating_add);
+        if hashed_end + 2 + merged_len + rest.len() > u16::MAX as usize {
+            return Err(PgpError::SubpacketTooLarge);
+        }
+
+        let data = prepare_packet(2, |data| {
+            data.extend(&packet[..hashed_end]);
+            write_subpackets(data, |unhashed_subpackets| {
+                for subpacket in &subpackets {
+                    let tag = if subpacket.critical {
+                        subpacket.tag | 0x80
+                    } else {
+                        subpacket.tag
+                    };
+                    write_single_subpacket(unhashed_subpackets, tag, |packet| {
+                        packet.extend(subpacket.data)
+                    });
+                }
+            });
+            data.extend(rest);
+        });
+        Ok(PgpSig { data })
+    }
+
+    /// Whether two signatures have the same hashed section and signature.
+    ///
+    /// Unlike `==`, this ignores the unhashed subpackets, which are not
+    /// covered by the signature, and the encoding of the signature MPIs.
+    /// Signatures which are canonically equal verify against the same data
+    /// and key.
+    pub fn canonical_eq(&self, other: &PgpSig) -> bool {
+        self.hashed_section() == other.hashed_section() && self.signature() == other.signature()
+    }
+
+    /// Get the portion of this signature hashed into the signed data.
+    ///
+    /// This is the version, signature type, public key algorithm and hash
+    /// algorithm octets, the two octet hashed subpacket length, and the
+    /// hashed subpackets: bytes `3..(9 + n)` of `as_bytes`, where `n` is
+    /// the hashed subpacket length and the first three bytes are the packet
+    /// header. It is followed by a trailer when hashed; see `hashed_region`
+    /// for the subpackets alone.
+    ///
+    /// The hashed subpacket length is checked against the packet length
+    /// when the signature is parsed, so this cannot read past the end of
+    /// the signature.
+    pub fn hashed_section(&self) -> &[u8] {
+        let subpackets_len = BigEndian::read_u16(&self.data[7..9]) as usize;
+        &self.data[3..(subpackets_len + 9)]
+    }
+
+    /// Get the hashed subpackets, without their length prefix.
+    ///
+    /// These are bytes `9..(9 + n)` of `as_bytes`, where `n` is the hashed
+    /// subpacket length stored in bytes `7..9`. Use `hashed_subpackets` to
+    /// iterate over them.
+    pub fn hashed_region(&self) -> &[u8] {
+        let subpackets_len = BigEndian::read_u16(&self.data[7..9]) as usize;
+        &self.data[9..(subpackets_len + 9)]
+    }
+
+    /// Get the unhashed subpackets, without their length prefix.
+    ///
+    /// These are bytes `(11 + n)..(11 + n + m)` of `as_bytes`, where `n`
+    /// is the hashed subpacket length and `m` is the unhashed subpacket
+    /// length stored in bytes `(9 + n)..(11 + n)`. Use
+    /// `unhashed_subpackets` to iterate over them.
+    pub fn unhashed_region(&self) -> &[u8] {
+        let init = BigEndian::read_u16(&self.data[7..9]) as usize + 9;
+        let subpackets_len = BigEndian::read_u16(&self.data[init..(init + 2)]) as usize;
+        &self.data[(init + 2)..(init + 2 + subpackets_len)]
+    }
+
+    /// Iterate over the subpackets in the hashed section of this signature.
+    ///
+    /// Iteration stops early if a subpacket is truncated or malformed.
+    pub fn hashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'_>> {
+        SubPacketIter {
+            data: self.hashed_region(),
+        }
+    }
+
+    /// Iterate over the subpackets in the unhashed section of this signature.
+    ///
+    /// These subpackets are not covered by the signature, so they can be
+    /// altered without invalidating it. Iteration stops early if a
+    /// subpacket is truncated or malformed.
+    pub fn unhashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'_>> {
+        SubPacketIter {
+            data: self.unhashed_region(),
+        }
+    }
+
+    /// Get the data of the first subpacket with this tag.
+    ///
+    /// The hashed section is searched before the unhashed section. The
+    /// critical bit is ignored when comparing tags.
+    pub fn subpacket(&self, tag: u8) -> Option<&[u8]> {
+        self.subpackets(tag).next()
+    }
+
+    /// Iterate over the data of every subpacket with this tag.
+    ///
+    /// Subpackets in the hashed section are yielded before those in the
+    /// unhashed section. The critical bit is ignored when comparing tags.
+    pub fn subpackets(&self, tag: u8) -> impl Iterator<Item = &[u8]> {
+        let tag = tag & 0x7f;
+        self.hashed_subpackets()
+            .chain(self.unhashed_subpackets())
+            .filter(move |subpacket| subpacket.tag == tag)
+            .map(|subpacket| subpacket.data)
+    }
+
+    /// Get the creation time of this signature, as a unix timestamp.
+    ///
+    /// This is read from the signature creation time subpacket in the
+    /// hashed section, and is `None` if that subpacket is absent.
+    pub fn timestamp(&self) -> Option<u32> {
+        self.hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 2 && subpacket.data.len() == 4)
+            .map(|subpacket| BigEndian::read_u32(subpacket.data))
+    }
+
+    /// Get the number of seconds after its creation at which this signature
+    /// expires.
+    ///
+    /// This is read from the signature expiration time subpacket in the
+    /// hashed section, and is `None` if that subpacket is absent.
+    pub fn expiration(&self) -> Option<u32> {
+        self.hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 3 && subpacket.data.len() == 4)
+            .map(|subpacket| BigEndian::read_u32(subpacket.data))
+    }
+
+    /// Whether this signature has expired at the unix time `now`.
+    ///
+    /// A signature without an expiration time, or with an expiration time
+    /// of zero, never expires.
+    pub fn is_expired(&self, now: u32) -> bool {
+        match (self.timestamp(), self.expiration()) {
+            (Some(timestamp), Some(expiration)) if expiration != 0 => {
+                now as u64 >= timestamp as u64 + expiration as u64
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Get the creation time of this signature as a chrono `DateTime`.
+    pub fn timestamp_datetime(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.timestamp()? as i64, 0)
+    }
+
+    /// The notations in the hashed subpackets of this signature.
+    ///
+    /// Malformed notation data subpackets are skipped.
+    pub fn notations(&self) -> Vec<Notation> {
+        self.hashed_subpackets()
+            .filter(|subpacket| subpacket.tag == Notation::TAG)
+            .filter_map(|subpacket| Notation::from_subpacket(subpacket.data).ok())
+            .collect()
+    }
+
+    /// The reason for revocation given by this signature, and the message
+    /// explaining it.
+    ///
+    /// This is read from the hashed reason for revocation subpacket (tag
+    /// 29). Returns `None` if there is no such subpacket, its reason code
+    /// is unknown, or its message is not valid UTF-8.
+    pub fn revocation_reason(&self) -> Option<(RevocationReason, &str)> {
+        let data = self
+            .hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 29)?
+            .data;
+        let (&code, message) = data.split_first()?;
+        let reason = RevocationReason::try_from(code).ok()?;
+        Some((reason, core::str::from_utf8(message).ok()?))
+    }
+
+    /// The level and amount of trust this certification delegates.
+    ///
+    /// This is read from the hashed trust signature subpacket (tag 5). See
+    /// `SubPacket::trust_signature_data`.
+    ///
+    /// This library does not check the trust signature and regular
+    /// expression subpackets when verifying. A caller which does should
+    /// pass their tags to `verify_critical_with` along with
+    /// `PgpSig::KNOWN_SUBPACKETS`, as they are often critical.
+    pub fn trust_signature(&self) -> Option<(u8, u8)> {
+        match self
+            .hashed_subpackets()
+            .find(|subpacket| subpacket.tag == SubPacket::TRUST_SIGNATURE)?
+            .data
+        {
+            &[level, amount] => Some((level, amount)),
+            _ => None,
+        }
+    }
+
+    /// The regular expression limiting the user ids this trust signature
+    /// applies to.
+    ///
+    /// This is read from the hashed regular expression subpacket (tag 6),
+    /// without its null terminator. Returns `None` if there is no such
+    /// subpacket, or it is not valid UTF-8.
+    pub fn trust_regex(&self) -> Option<&str> {
+        let data = self
+            .hashed_subpackets()
+            .find(|subpacket| subpacket.tag == SubPacket::REGULAR_EXPRESSION)?
+            .data;
+        let data = data.strip_suffix(&[0]).unwrap_or(data);
+        core::str::from_utf8(data).ok()
+    }
+
+    /// Get the actual ed25519 signature contained.
+    pub fn signature(&self) -> Signature {
+        read_signature_mpis(&self.data[3..]).expect("signature MPIs are checked when parsing")
+    }
+
+    /// Get the two halves of the ed25519 signature, `(r, s)`.
+    ///
+    /// These are the encoding of the EdDSA `R` point and of the `S`
+    /// scalar, as they appear in `signature`. In the packet they are two
+    /// MPIs, which omit leading zero octets; here each is padded back to 32
+    /// octets.
+    pub fn signature_components(&self) -> ([u8; 32], [u8; 32]) {
+        let signature = self.signature();
+        let mut r = [0; 32];
+        let mut s = [0; 32];
+        r.copy_from_slice(&signature[..32]);
+        s.copy_from_slice(&signature[32..]);
+        (r, s)
+    }
+
+    /// Get the fingerprint of the public key which made this signature.
+    ///
+    /// This is read from the issuer fingerprint subpacket in the hashed
+    /// section. An issuer fingerprint subpacket in the unhashed section is
+    /// checked to match it when the signature is parsed.
+    ///
+    /// A signature parsed with `from_bytes_legacy` may have no fingerprint
+    /// subpacket, in which case only the key ID from the unhashed issuer
+    /// subpacket is known. It is returned in the last 8 bytes of the
+    /// fingerprint, where a key ID is taken from, and the first 12 bytes
+    /// are zero. See `has_fingerprint`.
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.as_borrowed().fingerprint()
+    }
+
+    /// Whether this signature has an issuer fingerprint subpacket in its
+    /// hashed section.
+    ///
+    /// This is always true unless the signature was parsed with
+    /// `from_bytes_legacy`.
+    pub fn has_fingerprint(&self) -> bool {
+        has_fingerprint_subpacket(&self.data[3..])
+    }
+
+    // Whether this signature names the key with this fingerprint as its
+    // issuer. A signature without a fingerprint subpacket can only be
+    // matched by key ID.
+    pub(crate) fn is_issued_by(&self, fingerprint: &Fingerprint) -> bool {
+        if self.has_fingerprint() {
+            fingerprints_match(&self.fingerprint(), fingerprint)
+        } else {
+            self.key_id() == KeyId::from_fingerprint(fingerprint)
+        }
+    }
+
+    /// Get the key ID of the public key which made this signature.
+    ///
+    /// This is derived from the fingerprint in the hashed section, or for
+    /// a signature without one, read from the unhashed issuer subpacket.
+    pub fn key_id(&self) -> KeyId {
+        KeyId::from_fingerprint(&self.fingerprint())
+    }
+
+    /// Get the key ID from the unhashed issuer subpacket, if present.
+    ///
+    /// Because this subpacket is not covered by the signature, it may
+    /// disagree with `key_id`; a mismatch suggests tampering.
+    pub fn issuer_key_id(&self) -> Option<KeyId> {
+        self.unhashed_subpackets()
+            .find(|subpacket| subpacket.tag == 16 && subpacket.data.len() == 8)
+            .map(|subpacket| {
+                let mut key_id = [0; 8];
+                key_id.copy_from_slice(subpacket.data);
+                KeyId(key_id)
+            })
+    }
+
+    /// Get the type of this signature.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the signature type byte is not a known signature
+    /// type. Use `try_sig_type` when handling untrusted signatures.
+    pub fn sig_type(&self) -> SigType {
+        self.try_sig_type().expect("Unrecognized signature type.")
+    }
+
+    /// Get the type of this signature, or an error if the signature type
+    /// byte is not a known signature type.
+    pub fn try_sig_type(&self) -> Result<SigType, PgpError> {
+        SigType::try_from(self.data[4])
+    }
+
+    /// Whether this is a signature of a text document.
+    ///
+    /// Text document signatures are made over canonicalized text, and
+    /// should not be taken to cover the exact bytes of a binary file.
+    pub fn is_text(&self) -> bool {
+        matches!(self.try_sig_type(), Ok(SigType::TextDocument))
+    }
+
+    /// Whether this is a signature of a binary document.
+    pub fn is_binary(&self) -> bool {
+        matches!(self.try_sig_type(), Ok(SigType::BinaryDocument))
+    }
+
+    /// Whether this signature is made over a key rather than a document.
+    ///
+    /// See `SigType::certifies_key`. This is false if the signature type is
+    /// not recognized.
+    pub fn certifies_key(&self) -> bool {
+        self.try_sig_type().is_ok_and(SigType::certifies_key)
+    }
+
+    /// The first two bytes of the digest, as stored in this signature.
+    ///
+    /// This is a quick check which lets a verifier reject data which does
+    /// not match without checking the signature itself.
+    pub fn hash_prefix(&self) -> [u8; 2] {
+        let packet = &self.data[3..];
+        let hashed_len = BigEndian::read_u16(&packet[4..6]) as usize;
+        let unhashed_len = BigEndian::read_u16(&packet[(hashed_len + 6)..][..2]) as usize;
+        let init = hashed_len + unhashed_len + 8;
+        [packet[init], packet[init + 1]]
+    }
+
+    /// Get the public key algorithm ID this signature declares.
+    ///
+    /// This is always 22 (EdDSA), or with the `crypto-refresh` feature
+    /// possibly 27 (Ed25519), since signatures made with other algorithms
+    /// are rejected when parsed with
+    /// `PgpError::UnexpectedPublicKeyAlgorithm`.
+    pub fn public_key_algorithm(&self) -> u8 {
+        self.data[5]
+    }
+
+    /// Get the hash algorithm used to produce this signature.
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::try_from(self.data[6]).expect("hash algorithm is checked when parsed")
+    }
+
+    /// Verify data against this signature.
+    ///
+    /// The data to be verified should be inputed by hashing it into the
+    /// hasher using the input function. The hasher must implement the hash
+    /// algorithm this signature was made with; if its output size does not
+    /// match, verification fails. Verification also fails if the hash
+    /// algorithm is not one of `PgpSig::ALLOWED_HASH_ALGORITHMS`.
+    pub fn verify<D, F1, F2>(&self, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        let mut verifier = self.verifier::<D>();
+        input(&mut verifier.hasher);
+        verifier.finalize(verify)
+    }
+
+    /// Verify data against this signature, reporting why the digest was
+    /// rejected before the signature was checked.
+    ///
+    /// This is like `verify`, but returns an error if the hash algorithm is
+    /// not allowed (`PgpError::WeakHashAlgorithm`, as in
+    /// `check_hash_algorithm`), if the hasher does not implement the
+    /// signature's hash algorithm (`PgpError::HashAlgorithmMismatch`), or
+    /// if the first two bytes of the digest differ from `hash_prefix`
+    /// (`PgpError::HashPrefixMismatch`). The prefix is checked first, as gpg
+    /// does, so data which does not match is rejected without calling the
+    /// verify function. Otherwise, the result of the verify function is
+    /// returned.
+    pub fn try_verify<D, F1, F2>(&self, input: F1, verify: F2) -> Result<bool, PgpError>
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        let mut verifier = self.verifier::<D>();
+        input(&mut verifier.hasher);
+        verifier.try_finalize(verify)
+    }
+
+    /// Verify data against this signature, if it claims to be made by the
+    /// key with the expected fingerprint.
+    ///
+    /// The fingerprint in the signature is compared to `expected` before
+    /// the signature is checked, so a signature which names one key but was
+    /// made with another is rejected. With the `subtle` feature, the
+    /// fingerprints are compared in constant time. Otherwise, this behaves
+    /// like `verify`.
+    pub fn verify_with_fingerprint<D, F1, F2>(
+        &self,
+        expected: Fingerprint,
+        input: F1,
+        verify: F2,
+    ) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        fingerprints_match(&self.fingerprint(), &expected) && self.verify::<D, _, _>(input, verify)
+    }
+
+    /// Verify data against this signature, reporting why it was rejected.
+    ///
+    /// Unlike `verify`, which only checks the signature itself, this checks
+    /// in turn that:
+    ///  - the signature was made by the key with the expected fingerprint
+    ///  - it has no critical subpacket which this library does not
+    ///    recognize, as in `verify_critical`
+    ///  - it had not expired at the unix time `now`
+    ///  - its hash algorithm is one of `PgpSig::ALLOWED_HASH_ALGORITHMS`
+    ///  - the digest matches the hash algorithm and hash prefix, as in
+    ///    `try_verify`
+    ///  - the verify function accepts the signature
+    ///
+    /// The first check which fails is returned as the error.
+    pub fn verify_detailed<D, F1, F2>(
+        &self,
+        expected: Fingerprint,
+        now: u32,
+        input: F1,
+        verify: F2,
+    ) -> Result<(), VerifyError>
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        if !fingerprints_match(&self.fingerprint(), &expected) {
+            return Err(VerifyError::WrongKey);
+        }
+        if let Err(PgpError::UnknownCriticalSubpacket(tag)) =
+            self.check_critical_subpackets(PgpSig::KNOWN_SUBPACKETS)
+        {
+            return Err(VerifyError::UnknownCriticalSubpacket(tag));
+        }
+        if self.is_expired(now) {
+            return Err(VerifyError::Expired);
+        }
+        match self.try_verify::<D, _, _>(input, verify) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(VerifyError::BadSignature),
+            Err(PgpError::WeakHashAlgorithm(byte) | PgpError::DisallowedHashAlgorithm(byte)) => {
+                Err(VerifyError::DisallowedHashAlgorithm(byte))
+            }
+            Err(PgpError::HashAlgorithmMismatch) => Err(VerifyError::HashAlgorithmMismatch),
+            Err(PgpError::HashPrefixMismatch) => Err(VerifyError::HashPrefixMismatch),
+            // `finalize_verify` only fails on the hash algorithm policy, the
+            // hasher's algorithm or the hash prefix, all matched above.
+            Err(err) => unreachable!("unexpected error from try_verify: {:?}", err),
+        }
+    }
+
+    /// Verify data against this signature, if it was made for the given
+    /// application context.
+    ///
+    /// The signature must have exactly one hashed `PgpSig::CONTEXT_NOTATION`
+    /// notation, and its value must equal the context; otherwise this
+    /// returns false without checking the signature. See
+    /// `new_with_context`. Otherwise, this behaves like `verify`.
+    pub fn verify_with_context<D, F1, F2>(&self, context: &str, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        let mut contexts = self
+            .notations()
+            .into_iter()
+            .filter(|notation| notation.name == PgpSig::CONTEXT_NOTATION);
+        match (contexts.next(), contexts.next()) {
+            (Some(notation), None) if notation.value == context.as_bytes() => {
+                self.verify::<D, _, _>(input, verify)
+            }
+            _ => false,
+        }
+    }
+
+    /// Verify data against this signature, rejecting it if it has a
+    /// critical subpacket which this library does not recognize.
+    ///
+    /// RFC 4880 requires that a signature with an unrecognized critical
+    /// subpacket be treated as invalid. Otherwise, this behaves like
+    /// `verify`. The recognized subpackets are `PgpSig::KNOWN_SUBPACKETS`.
+    pub fn verify_critical<D, F1, F2>(&self, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        self.verify_critical_with::<D, _, _>(PgpSig::KNOWN_SUBPACKETS, input, verify)
+    }
+
+    /// Verify data against this signature, rejecting it if it has a
+    /// critical subpacket whose tag is not in the known tags.
+    ///
+    /// This allows callers which handle other subpackets themselves to
+    /// accept signatures where those subpackets are critical.
+    pub fn verify_critical_with<D, F1, F2>(&self, known: &[u8], input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        self.check_critical_subpackets(known).is_ok() && self.verify::<D, _, _>(input, verify)
+    }
+
+    /// Check that every critical hashed subpacket of this signature has one
+    /// of the known tags.
+    ///
+    /// Returns the tag of the first unknown critical subpacket as an error.
+    pub fn check_critical_subpackets(&self, known: &[u8]) -> Result<(), PgpError> {
+        match self
+            .hashed_subpackets()
+            .find(|subpacket| subpacket.critical && !known.contains(&subpacket.tag))
+        {
+            Some(subpacket) => Err(PgpError::UnknownCriticalSubpacket(subpacket.tag)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that this signature was made with one of the allowed hash
+    /// algorithms.
+    ///
+    /// This lets a caller apply a stricter policy than
+    /// `PgpSig::ALLOWED_HASH_ALGORITHMS`, such as only accepting SHA-512,
+    /// by checking signatures before verifying them. Returns
+    /// `PgpError::WeakHashAlgorithm` if the algorithm is not allowed and is
+    /// MD5, SHA-1 or RIPEMD-160, and `PgpError::DisallowedHashAlgorithm`
+    /// for any other algorithm which is not allowed.
+    pub fn check_hash_algorithm(&self, allowed: &[HashAlgorithm]) -> Result<(), PgpError> {
+        check_hash_algorithm(self.hash_algorithm(), allowed)
+    }
+
+    /// Begin verifying data against this signature incrementally.
+    ///
+    /// The data can be passed to the returned `Verifier` in pieces as it
+    /// arrives, rather than all at once as with `verify`.
+    pub fn verifier<D: Digest>(&self) -> Verifier<'_, D> {
+        Verifier {
+            sig: self,
+            hasher: D::default(),
+            allowed: PgpSig::ALLOWED_HASH_ALGORITHMS,
+        }
+    }
+
+    /// Verify a standalone or timestamp signature.
+    ///
+    /// Only the signature's own hashed section is hashed, as there is no
+    /// document data. This returns false for any other type of signature.
+    pub fn verify_standalone<D, F>(&self, verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8], Signature) -> bool,
+    {
+        matches!(
+            self.try_sig_type(),
+            Ok(SigType::Standalone | SigType::Timestamp)
+        ) && self.verify::<D, _, _>(|_| {}, verify)
+    }
+
+    /// Verify text against this signature.
+    ///
+    /// The text is canonicalized as in `new_text` before it is hashed, so
+    /// differences in line endings or trailing whitespace do not cause
+    /// verification to fail.
+    pub fn verify_text<D, F>(&self, text: &[u8], verify: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&[u8], Signature) -> bool,
+    {
+        self.verify::<D, _, _>(|hasher| hash_canonical_text(hasher, text), verify)
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Convert this signature from an ed25519-dalek signature.
+    pub fn from_dalek<Sha256, Sha512>(
+        keypair: &dalek::SigningKey,
+        data: &[u8],
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        timestamp: u32,
+    ) -> PgpSig
+    where
+        Sha256: Digest<OutputSize = U32>,
+        Sha512: Digest<OutputSize = U64>,
+    {
+        PgpSig::from_dalek_with_subpackets::<Sha256, Sha512>(
+            keypair,
+            data,
+            fingerprint,
+            sig_type,
+            timestamp,
+            &[],
+        )
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Convert this signature from an ed25519-dalek signature, with
+    /// additional hashed subpackets.
+    ///
+    /// The subpackets are written as in `new`.
+    pub fn from_dalek_with_subpackets<Sha256, Sha512>(
+        keypair: &dalek::SigningKey,
+        data: &[u8],
+        fingerprint: Fingerprint,
+        sig_type: SigType,
+        timestamp: u32,
+        subpackets: &[SubPacket],
+    ) -> PgpSig
+    where
+        Sha256: Digest<OutputSize = U32>,
+        Sha512: Digest<OutputSize = U64>,
+    {
+        PgpSig::new::<Sha256, _>(
+            data,
+            fingerprint,
+            sig_type,
+            HashAlgorithm::Sha256,
+            timestamp,
+            None,
+            subpackets,
+            |data| keypair.sign(data).to_bytes(),
+        )
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Construct a signature with an ed25519-dalek key, deriving the
+    /// fingerprint from the key itself.
+    ///
+    /// Unlike `from_dalek`, the issuer fingerprint cannot disagree with the
+    /// key which made the signature. The fingerprint of an OpenPGP key
+    /// covers its creation time, so `key_creation_time` must be the same
+    /// time the `PgpKey` was created with; `unix_time` is the time of the
+    /// signature.
+    pub fn new_checked<Sha256, Sha512>(
+        keypair: &dalek::SigningKey,
+        key_creation_time: u32,
+        data: &[u8],
+        sig_type: SigType,
+        unix_time: u32,
+        subpackets: &[SubPacket],
+    ) -> PgpSig
+    where
+        Sha256: Digest<OutputSize = U32>,
+        Sha512: Digest<OutputSize = U64>,
+    {
+        let mut key_packet = Vec::with_capacity(54);
+        write_public_key_packet(
+            &mut key_packet,
+            6,
+            keypair.verifying_key().as_bytes(),
+            key_creation_time,
+        );
+        PgpSig::from_dalek_with_subpackets::<Sha256, Sha512>(
+            keypair,
+            data,
+            fingerprint(&key_packet),
+            sig_type,
+            unix_time,
+            subpackets,
+        )
+    }
+
+    /// Verify data against this signature and an ed25519 public key, using
+    /// the given ed25519 implementation.
+    ///
+    /// This behaves like `verify`, with a verify function which calls
+    /// `B::verify` with the public key. See the `backend` module.
+    pub fn verify_with<D, B, F>(&self, public_key: &[u8; 32], input: F) -> bool
+    where
+        D: Digest,
+        B: VerifyBackend,
+        F: FnOnce(&mut D),
+    {
+        self.verify::<D, _, _>(input, |data, signature| {
+            B::verify(public_key, data, signature)
+        })
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Convert this signature to an ed25519-dalek signature.
+    pub fn to_dalek(&self) -> dalek::Signature {
+        dalek::Signature::from_bytes(&self.signature())
+    }
 
-                for &SubPacket { tag, data } in subpackets {
-                    write_single_subpacket(hashed_subpackets, tag, |packet| packet.extend(data));
-                }
-            });
+    #[cfg(feature = "dalek")]
+    /// Verify this signature against an ed25519-dalek public key.
+    ///
+    /// The first type parameter is the hasher for the hash algorithm this
+    /// signature was made with, such as SHA-256 or SHA-512.
+    pub fn verify_dalek<D, Sha512, F>(&self, key: &dalek::VerifyingKey, input: F) -> bool
+    where
+        D: Digest,
+        Sha512: Digest<OutputSize = U64>,
+        F: FnOnce(&mut D),
+    {
+        self.verify::<D, _, _>(input, |data, signature| {
+            let sig = dalek::Signature::from_bytes(&signature);
+            key.verify_strict(data, &sig).is_ok()
+        })
+    }
 
-            let hash = {
-                let mut hasher = Sha256::default();
+    #[cfg(feature = "batch")]
+    /// Verify many signatures made by the same key in a single batch.
+    ///
+    /// Each signature is checked against the message at the same index,
+    /// hashed the same way as in `verify`. If any signature fails, the
+    /// indices of the failing signatures are returned.
+    ///
+    /// Batch verification does not reject weak keys and signatures the way
+    /// `verify_dalek` does, so it should only be used with a trusted key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigs` and `messages` have different lengths.
+    pub fn verify_batch<D: Digest>(
+        sigs: &[PgpSig],
+        messages: &[&[u8]],
+        key: &dalek::VerifyingKey,
+    ) -> Result<(), Vec<usize>> {
+        assert_eq!(
+            sigs.len(),
+            messages.len(),
+            "each signature must have one message"
+        );
 
-                hasher.process(data);
+        let mut failed = Vec::new();
+        let mut indices = Vec::with_capacity(sigs.len());
+        let mut digests = Vec::with_capacity(sigs.len());
+        let mut signatures = Vec::with_capacity(sigs.len());
 
-                hasher.process(&packet[3..]);
+        for (idx, (sig, message)) in sigs.iter().zip(messages).enumerate() {
+            let mut verifier = sig.verifier::<D>();
+            verifier.update(message);
+            let hashed = verifier.finalize(|hash, signature| {
+                digests.push(hash.to_owned());
+                signatures.push(dalek::Signature::from_bytes(&signature));
+                true
+            });
+            if hashed {
+                indices.push(idx);
+            } else {
+                failed.push(idx);
+            }
+        }
 
-                hasher.process(&[0x04, 0xff]);
-                hasher.process(&bigendian_u32((packet.len() - 3) as u32));
+        let digests: Vec<&[u8]> = digests.iter().map(|digest| &digest[..]).collect();
+        let keys = vec![*key; digests.len()];
+        if !digests.is_empty() && dalek::verify_batch(&digests, &signatures, &keys).is_err() {
+            // The batch failed; check each signature alone to find which
+            for (idx, (digest, signature)) in indices.iter().zip(digests.iter().zip(&signatures)) {
+                if key.verify_strict(digest, signature).is_err() {
+                    failed.push(*idx);
+                }
+            }
+            failed.sort_unstable();
+        }
 
-                hasher.fixed_result()
-            };
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(failed)
+        }
+    }
+}
 
-            write_subpackets(packet, |unhashed_subpackets| {
-                write_single_subpacket(unhashed_subpackets, 16, |packet| {
-                    packet.extend(&fingerprint[12..]);
-                });
-            });
+/// A PGP signature which has been prepared but not yet signed.
+///
+/// This is returned by `PgpSig::prepare`. The digest can be signed
+/// anywhere, such as by a remote signing service, and the signature passed
+/// to `finalize`.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct UnsignedSig {
+    // the signature packet body, up to and including the hash prefix
+    body: Vec<u8>,
+    digest: Vec<u8>,
+}
 
-            packet.extend(&hash[0..2]);
+impl UnsignedSig {
+    /// The digest to be signed with ed25519.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
 
-            let signature = sign(&hash[..]);
+    /// Complete the signature with the ed25519 signature of the digest.
+    ///
+    /// The signature is not checked.
+    pub fn finalize(self, signature: Signature) -> PgpSig {
+        let data = prepare_packet(2, |packet| {
+            packet.extend(&self.body);
             write_mpi(packet, &signature[00..32]);
             write_mpi(packet, &signature[32..64]);
         });
-
         PgpSig { data }
     }
+}
 
-    /// Parse an OpenPGP signature from binary data.
-    ///
-    /// This must be an ed25519 signature using SHA-256 for hashing,
-    /// and it must be in the subset of OpenPGP supported by this library.
-    pub fn from_bytes(bytes: &[u8]) -> Result<PgpSig, PgpError> {
-        // TODO: convert to three byte header
-        let (data, packet) = find_signature_packet(bytes)?;
-        has_correct_structure(packet)?;
-        has_correct_hashed_subpackets(packet)?;
-        Ok(PgpSig { data })
+impl Debug for UnsignedSig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UnsignedSig")
+            .field("digest", &Base64(&self.digest))
+            .finish()
     }
+}
 
-    /// Parse an OpenPGP signature from ASCII armored data.
-    pub fn from_ascii_armor(string: &str) -> Result<PgpSig, PgpError> {
-        let data = remove_ascii_armor(string, "BEGIN PGP SIGNATURE", "END PGP SIGNATURE")?;
-        PgpSig::from_bytes(&data)
-    }
+/// An OpenPGP formatted ed25519 signature borrowed from a byte slice.
+///
+/// Created with `PgpSig::from_bytes_borrowed`, or with
+/// `PgpSig::as_borrowed`. This has the read-only methods of `PgpSig`
+/// needed to check a signature, none of which allocate.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PgpSigRef<'a> {
+    // The same invariants hold as for the data of a PgpSig.
+    data: &'a [u8],
+}
 
+impl<'a> PgpSigRef<'a> {
     /// Get the binary representation of this signature.
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.data
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
     }
 
-    /// Get the portion of this signature hashed into the signed data.
-    pub fn hashed_section(&self) -> &[u8] {
+    /// Copy this signature into an owned `PgpSig`.
+    pub fn to_pgp_sig(&self) -> PgpSig {
+        PgpSig {
+            data: self.data.to_owned(),
+        }
+    }
+
+    /// Get the hashed section of this signature, as in
+    /// `PgpSig::hashed_section`.
+    pub fn hashed_section(&self) -> &'a [u8] {
         let subpackets_len = BigEndian::read_u16(&self.data[7..9]) as usize;
         &self.data[3..(subpackets_len + 9)]
     }
 
-    /// Get the actual ed25519 signature contained.
+    /// Iterate over the subpackets in the hashed section of this signature.
+    pub fn hashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'a>> {
+        let subpackets_len = BigEndian::read_u16(&self.data[7..9]) as usize;
+        SubPacketIter {
+            data: &self.data[9..(subpackets_len + 9)],
+        }
+    }
+
+    /// Iterate over the subpackets in the unhashed section of this
+    /// signature.
+    pub fn unhashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'a>> {
+        let init = BigEndian::read_u16(&self.data[7..9]) as usize + 9;
+        let subpackets_len = BigEndian::read_u16(&self.data[init..(init + 2)]) as usize;
+        SubPacketIter {
+            data: &self.data[(init + 2)..(init + 2 + subpackets_len)],
+        }
+    }
+
+    /// Get the unix timestamp at which this signature was made, as in
+    /// `PgpSig::timestamp`.
+    pub fn timestamp(&self) -> Option<u32> {
+        self.hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 2 && subpacket.data.len() == 4)
+            .map(|subpacket| BigEndian::read_u32(subpacket.data))
+    }
+
+    /// Get the ed25519 signature, as in `PgpSig::signature`.
     pub fn signature(&self) -> Signature {
-        let init = self.data.len() - 68;
-        let sig_data = &self.data[init..];
-        let mut sig = [0; 64];
-        sig[00..32].clone_from_slice(&sig_data[2..34]);
-        sig[32..64].clone_from_slice(&sig_data[36..68]);
-        sig
+        read_signature_mpis(&self.data[3..]).expect("signature MPIs are checked when parsing")
     }
 
-    /// Get the fingerprint of the public key which made this signature.
+    /// Get the fingerprint of the public key which made this signature, as
+    /// in `PgpSig::fingerprint`.
     pub fn fingerprint(&self) -> Fingerprint {
         let mut fingerprint = [0; 20];
-        fingerprint.clone_from_slice(&self.data[10..30]);
-        fingerprint
+        if has_fingerprint_subpacket(&self.data[3..]) {
+            fingerprint.clone_from_slice(&self.data[12..32]);
+        } else if let Some(key_id) = self.issuer_key_id() {
+            fingerprint[12..].copy_from_slice(&key_id.0);
+        }
+        Fingerprint(fingerprint)
     }
 
-    /// Get the type of this signature.
-    pub fn sig_type(&self) -> SigType {
-        match self.data[4] {
-            0x00 => SigType::BinaryDocument,
-            0x01 => SigType::TextDocument,
-            0x02 => SigType::Standalone,
-            0x10 => SigType::GenericCertification,
-            0x11 => SigType::PersonaCertification,
-            0x12 => SigType::CasualCertification,
-            0x13 => SigType::PositiveCertification,
-            0x18 => SigType::SubkeyBinding,
-            0x19 => SigType::PrimaryKeyBinding,
-            0x1F => SigType::DirectlyOnKey,
-            0x20 => SigType::KeyRevocation,
-            0x28 => SigType::SubkeyRevocation,
-            0x30 => SigType::CertificationRevocation,
-            0x40 => SigType::Timestamp,
-            0x50 => SigType::ThirdPartyConfirmation,
-            _ => panic!("Unrecognized signature type."),
-        }
+    /// Get the key ID from the unhashed issuer subpacket, if present.
+    pub fn issuer_key_id(&self) -> Option<KeyId> {
+        self.unhashed_subpackets()
+            .find(|subpacket| subpacket.tag == 16 && subpacket.data.len() == 8)
+            .map(|subpacket| {
+                let mut key_id = [0; 8];
+                key_id.copy_from_slice(subpacket.data);
+                KeyId(key_id)
+            })
     }
 
-    /// Verify data against this signature.
-    ///
-    /// The data to be verified should be inputed by hashing it into the
-    /// SHA-256 hasher using the input function.
-    pub fn verify<Sha256, F1, F2>(&self, input: F1, verify: F2) -> bool
+    /// Get the key ID of the public key which made this signature.
+    pub fn key_id(&self) -> KeyId {
+        KeyId::from_fingerprint(&self.fingerprint())
+    }
+
+    /// Get the type of this signature, or an error if the signature type
+    /// byte is not a known signature type.
+    pub fn try_sig_type(&self) -> Result<SigType, PgpError> {
+        SigType::try_from(self.data[4])
+    }
+
+    /// Get the first two bytes of the digest this signature was made over.
+    pub fn hash_prefix(&self) -> [u8; 2] {
+        let packet = &self.data[3..];
+        let hashed_len = BigEndian::read_u16(&packet[4..6]) as usize;
+        let unhashed_len = BigEndian::read_u16(&packet[(hashed_len + 6)..][..2]) as usize;
+        let init = hashed_len + unhashed_len + 8;
+        [packet[init], packet[init + 1]]
+    }
+
+    /// Get the public key algorithm ID this signature declares.
+    pub fn public_key_algorithm(&self) -> u8 {
+        self.data[5]
+    }
+
+    /// Get the hash algorithm used to produce this signature.
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::try_from(self.data[6]).expect("hash algorithm is checked when parsed")
+    }
+
+    /// Verify data against this signature, as in `PgpSig::verify`.
+    pub fn verify<D, F1, F2>(&self, input: F1, verify: F2) -> bool
     where
-        Sha256: Digest<OutputSize = U32>,
-        F1: FnOnce(&mut Sha256),
+        D: Digest,
+        F1: FnOnce(&mut D),
         F2: FnOnce(&[u8], Signature) -> bool,
     {
-        let hash = {
-            let mut hasher = Sha256::default();
+        self.try_verify(input, verify).unwrap_or(false)
+    }
 
-            input(&mut hasher);
+    /// Verify data against this signature, reporting why the digest was
+    /// rejected before the signature was checked, as in
+    /// `PgpSig::try_verify`.
+    pub fn try_verify<D, F1, F2>(&self, input: F1, verify: F2) -> Result<bool, PgpError>
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], Signature) -> bool,
+    {
+        let mut hasher = D::default();
+        input(&mut hasher);
+        finalize_verify(*self, hasher, PgpSig::ALLOWED_HASH_ALGORITHMS, verify)
+    }
+}
 
-            let hashed_section = self.hashed_section();
-            hasher.process(hashed_section);
+impl<'a> Debug for PgpSigRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PgpSigRef")
+            .field("key", &Base64(self.data))
+            .finish()
+    }
+}
 
-            hasher.process(&[0x04, 0xff]);
-            hasher.process(&bigendian_u32(hashed_section.len() as u32));
+/// An in-progress verification of data against a signature.
+///
+/// Created with `PgpSig::verifier`. Feed the signed data to `update`, then
+/// call `finalize` to check the signature.
+pub struct Verifier<'a, D> {
+    sig: &'a PgpSig,
+    hasher: D,
+    allowed: &'a [HashAlgorithm],
+}
 
-            hasher.fixed_result()
-        };
+impl<'a, D: Digest> Verifier<'a, D> {
+    /// Accept signatures made with the given hash algorithms, instead of
+    /// `PgpSig::ALLOWED_HASH_ALGORITHMS`.
+    ///
+    /// This can restrict verification to fewer algorithms, or, for old
+    /// signatures which cannot be replaced, allow a weak one such as
+    /// `HashAlgorithm::Sha1`.
+    pub fn allow_hash_algorithms(mut self, allowed: &'a [HashAlgorithm]) -> Verifier<'a, D> {
+        self.allowed = allowed;
+        self
+    }
 
-        verify(&hash[..], self.signature())
+    /// Hash the next chunk of the signed data.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.process(chunk);
     }
 
-    #[cfg(feature = "dalek")]
-    /// Convert this signature from an ed25519-dalek signature.
-    pub fn from_dalek<Sha256, Sha512>(
-        keypair: &dalek::SigningKey,
-        data: &[u8],
-        fingerprint: Fingerprint,
-        sig_type: SigType,
-        timestamp: u32,
-    ) -> PgpSig
+    /// Finish hashing and verify the signature.
+    ///
+    /// The verify function is called as in `PgpSig::verify`.
+    pub fn finalize<F>(self, verify: F) -> bool
     where
-        Sha256: Digest<OutputSize = U32>,
-        Sha512: Digest<OutputSize = U64>,
+        F: FnOnce(&[u8], Signature) -> bool,
     {
-        PgpSig::new::<Sha256, _>(data, fingerprint, sig_type, timestamp, &[], |data| {
-            keypair.sign(data).to_bytes()
-        })
-    }
-
-    #[cfg(feature = "dalek")]
-    /// Convert this signature to an ed25519-dalek signature.
-    pub fn to_dalek(&self) -> dalek::Signature {
-        dalek::Signature::from_bytes(&self.signature())
+        self.try_finalize(verify).unwrap_or(false)
     }
 
-    #[cfg(feature = "dalek")]
-    /// Verify this signature against an ed25519-dalek public key.
-    pub fn verify_dalek<Sha256, Sha512, F>(&self, key: &dalek::VerifyingKey, input: F) -> bool
+    /// Finish hashing and verify the signature, reporting why the digest
+    /// was rejected before the signature was checked.
+    ///
+    /// See `PgpSig::try_verify`.
+    pub fn try_finalize<F>(self, verify: F) -> Result<bool, PgpError>
     where
-        Sha256: Digest<OutputSize = U32>,
-        Sha512: Digest<OutputSize = U64>,
-        F: FnOnce(&mut Sha256),
+        F: FnOnce(&[u8], Signature) -> bool,
     {
-        self.verify::<Sha256, _, _>(input, |data, signature| {
-            let sig = dalek::Signature::from_bytes(&signature);
-            key.verify_strict(data, &sig).is_ok()
-        })
+        finalize_verify(self.sig.as_borrowed(), self.hasher, self.allowed, verify)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, D: Digest> std::io::Write for Verifier<'a, D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, D> Debug for Verifier<'a, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Verifier").field("sig", self.sig).finish()
     }
 }
 
@@ -273,17 +2256,20 @@ impl Debug for PgpSig {
     }
 }
 
+#[cfg(feature = "std")]
 impl Display for PgpSig {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         ascii_armor(
             "BEGIN PGP SIGNATURE",
             "END PGP SIGNATURE",
+            &[],
             &self.data[..],
             f,
         )
     }
 }
 
+#[cfg(feature = "std")]
 impl FromStr for PgpSig {
     type Err = PgpError;
     fn from_str(s: &str) -> Result<PgpSig, PgpError> {
@@ -291,22 +2277,115 @@ impl FromStr for PgpSig {
     }
 }
 
-fn find_signature_packet(data: &[u8]) -> Result<(Vec<u8>, &[u8]), PgpError> {
-    let (init, len) = match data.first() {
-        Some(&0x88) => {
+impl PartialEq<[u8]> for PgpSig {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.data[..] == *other
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for PgpSig {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.data[..] == **other
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<str> for PgpSig {
+    fn eq(&self, other: &str) -> bool {
+        match PgpSig::from_ascii_armor(other) {
+            Ok(sig) => self.as_bytes() == sig.as_bytes(),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> PartialEq<&'a str> for PgpSig {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self == **other
+    }
+}
+
+// Hashes text with CRLF line endings and trailing whitespace removed.
+pub(crate) fn hash_canonical_text<D: Digest>(hasher: &mut D, text: &[u8]) {
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .unwrap_or(rest.len());
+        let line = &rest[..end];
+        let trimmed_len = line
+            .iter()
+            .rposition(|&b| b != b' ' && b != b'\t')
+            .map_or(0, |i| i + 1);
+        hasher.process(&line[..trimmed_len]);
+
+        if end == rest.len() {
+            break;
+        }
+        hasher.process(b"\r\n");
+        let newline_len = if rest[end..].starts_with(b"\r\n") {
+            2
+        } else {
+            1
+        };
+        rest = &rest[(end + newline_len)..];
+    }
+}
+
+// Returns the packet with a three byte header, and any data after the packet.
+pub(crate) fn find_signature_packet(data: &[u8]) -> Result<(Vec<u8>, &[u8]), PgpError> {
+    let (init, len) = read_signature_header(data)?;
+
+    if data.len() < init + len {
+        return Err(PgpError::InvalidPacketHeader);
+    }
+
+    let packet = &data[init..][..len];
+    let rest = &data[(init + len)..];
+
+    if init == 3 && data[0] == 0x89 {
+        Ok((data[..(init + len)].to_owned(), rest))
+    } else {
+        let mut vec = Vec::with_capacity(3 + len);
+        let len = bigendian_u16(len as u16);
+        vec.push(0x89);
+        vec.push(len[0]);
+        vec.push(len[1]);
+        vec.extend(packet.iter().cloned());
+        Ok((vec, rest))
+    }
+}
+
+// Parses the header of a signature packet, returning the length of the
+// header and of the packet body. Packets of any other type are rejected, so
+// that their bodies are never read as a signature.
+fn read_signature_header(data: &[u8]) -> Result<(usize, usize), PgpError> {
+    let header = *data.first().ok_or(PgpError::InvalidPacketHeader)?;
+    let tag = packet_tag(header)?;
+    if tag != 2 {
+        return Err(PgpError::UnexpectedPacketType {
+            expected: 2,
+            found: tag,
+        });
+    }
+
+    match header {
+        0x88 => {
             if data.len() < 2 {
                 return Err(PgpError::InvalidPacketHeader);
             }
-            (2, data[1] as usize)
+            Ok((2, data[1] as usize))
         }
-        Some(&0x89) => {
+        0x89 => {
             if data.len() < 3 {
                 return Err(PgpError::InvalidPacketHeader);
             }
             let len = BigEndian::read_u16(&data[1..3]);
-            (3, len as usize)
+            Ok((3, len as usize))
         }
-        Some(&0x8a) => {
+        0x8a => {
             if data.len() < 5 {
                 return Err(PgpError::InvalidPacketHeader);
             }
@@ -314,62 +2393,194 @@ fn find_signature_packet(data: &[u8]) -> Result<(Vec<u8>, &[u8]), PgpError> {
             if len > u16::MAX as u32 {
                 return Err(PgpError::UnsupportedPacketLength);
             }
-            (5, len as usize)
+            Ok((5, len as usize))
         }
-        _ => return Err(PgpError::UnsupportedPacketLength),
-    };
-
-    if data.len() < init + len {
-        return Err(PgpError::InvalidPacketHeader);
+        0xc2 => {
+            let (len_len, len) = read_new_format_length(&data[1..])?;
+            Ok((1 + len_len, len))
+        }
+        _ => Err(PgpError::UnsupportedPacketLength),
     }
+}
 
-    let packet = &data[init..][..len];
-
-    if init == 3 {
-        Ok((data.to_owned(), packet))
-    } else {
-        let mut vec = Vec::with_capacity(3 + len);
-        let len = bigendian_u16(len as u16);
-        vec.push(0x89);
-        vec.push(len[0]);
-        vec.push(len[1]);
-        vec.extend(packet.iter().cloned());
-        Ok((vec, packet))
+#[cfg(feature = "std")]
+// Reads a signature packet header from the reader, a byte at a time so that
+// nothing after it is consumed.
+fn read_signature_header_from<R: Read>(reader: &mut R) -> Result<Vec<u8>, PgpError> {
+    let mut header = Vec::with_capacity(6);
+    loop {
+        let mut byte = [0];
+        read_exact(reader, &mut byte)?;
+        header.push(byte[0]);
+        let complete = match (header[0], header.len()) {
+            (0x88, len) => len == 2,
+            (0x89, len) => len == 3,
+            (0x8a, len) => len == 5,
+            (0xc2, 1) => false,
+            (0xc2, len) => match header[1] {
+                192..=223 => len == 3,
+                255 => len == 6,
+                _ => true,
+            },
+            _ => true,
+        };
+        if complete {
+            return Ok(header);
+        }
     }
 }
 
+#[cfg(feature = "std")]
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), PgpError> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => PgpError::UnexpectedEof,
+        _ => PgpError::Io(err),
+    })
+}
+
 fn has_correct_structure(packet: &[u8]) -> Result<(), PgpError> {
     if packet.len() < 6 {
         return Err(PgpError::UnsupportedSignaturePacket);
     }
 
-    if !(packet[0] == 4 && packet[2] == 22 && packet[3] == 8) {
-        return Err(PgpError::UnsupportedSignaturePacket);
+    if packet[0] != 4 {
+        return Err(PgpError::UnexpectedSignatureVersion(packet[0]));
+    }
+
+    if packet[2] != 22 && !(cfg!(feature = "crypto-refresh") && packet[2] == 27) {
+        return Err(PgpError::UnexpectedPublicKeyAlgorithm(packet[2]));
     }
 
+    HashAlgorithm::try_from(packet[3])?;
+
     let hashed_len = BigEndian::read_u16(&packet[4..6]) as usize;
     if packet.len() < hashed_len + 8 {
-        return Err(PgpError::UnsupportedSignaturePacket);
+        return Err(PgpError::HashedSubpacketLengthOverflow);
     }
 
     let unhashed_len = BigEndian::read_u16(&packet[(hashed_len + 6)..][..2]) as usize;
-    if packet.len() != unhashed_len + hashed_len + 78 {
+    if packet.len() < hashed_len + unhashed_len + 10 {
         return Err(PgpError::UnsupportedSignaturePacket);
     }
 
+    read_signature_mpis(packet)?;
+
     Ok(())
 }
 
-fn has_correct_hashed_subpackets(packet: &[u8]) -> Result<(), PgpError> {
+// Read the two MPIs at the end of a signature packet, left-padding each to
+// 32 bytes. Ed25519 (27) signatures store the 64 signature octets as they
+// are, rather than as MPIs.
+fn read_signature_mpis(packet: &[u8]) -> Result<Signature, PgpError> {
     let hashed_len = BigEndian::read_u16(&packet[4..6]) as usize;
-    if hashed_len < 23 {
+    let unhashed_len = BigEndian::read_u16(&packet[(hashed_len + 6)..][..2]) as usize;
+    // skip the unhashed subpackets and the two byte hash prefix
+    let mpis = &packet[(hashed_len + unhashed_len + 10)..];
+
+    if packet[2] == 27 {
+        let mut sig = [0; 64];
+        match mpis.len() {
+            64 => sig.copy_from_slice(mpis),
+            len if len > 64 => return Err(PgpError::TrailingDataAfterSignature),
+            _ => return Err(PgpError::UnsupportedSignaturePacket),
+        }
+        return Ok(sig);
+    }
+
+    let (r, rest) = read_mpi(mpis)?;
+    let (s, rest) = read_mpi(rest)?;
+    if r.len() > 32 || s.len() > 32 {
+        return Err(PgpError::MalformedMpi);
+    }
+    if !rest.is_empty() {
+        return Err(PgpError::TrailingDataAfterSignature);
+    }
+
+    let mut sig = [0; 64];
+    sig[(32 - r.len())..32].copy_from_slice(r);
+    sig[(64 - s.len())..64].copy_from_slice(s);
+    Ok(sig)
+}
+
+// Finish hashing a signature's hashed section and trailer into the digest
+// of the signed data, and check the digest before calling the verify
+// function. The hash algorithm must be one of the allowed algorithms.
+fn finalize_verify<D, F>(
+    sig: PgpSigRef<'_>,
+    mut hasher: D,
+    allowed: &[HashAlgorithm],
+    verify: F,
+) -> Result<bool, PgpError>
+where
+    D: Digest,
+    F: FnOnce(&[u8], Signature) -> bool,
+{
+    check_hash_algorithm(sig.hash_algorithm(), allowed)?;
+
+    if D::OutputSize::to_usize() != sig.hash_algorithm().digest_len() {
+        return Err(PgpError::HashAlgorithmMismatch);
+    }
+
+    let hash = {
+        let hashed_section = sig.hashed_section();
+        hasher.process(hashed_section);
+
+        hasher.process(&[0x04, 0xff]);
+        hasher.process(&bigendian_u32(hashed_section.len() as u32));
+
+        hasher.fixed_result()
+    };
+
+    if hash[0..2] != sig.hash_prefix() {
+        return Err(PgpError::HashPrefixMismatch);
+    }
+
+    Ok(verify(&hash[..], sig.signature()))
+}
+
+fn check_hash_algorithm(
+    hash_algorithm: HashAlgorithm,
+    allowed: &[HashAlgorithm],
+) -> Result<(), PgpError> {
+    if allowed.contains(&hash_algorithm) {
+        Ok(())
+    } else if hash_algorithm.is_weak() {
+        Err(PgpError::WeakHashAlgorithm(hash_algorithm.as_byte()))
+    } else {
+        Err(PgpError::DisallowedHashAlgorithm(hash_algorithm.as_byte()))
+    }
+}
+
+// Check that any issuer fingerprint subpackets in the unhashed section match
+// the one in the hashed section.
+fn has_matching_fingerprints(sig: PgpSigRef<'_>) -> Result<(), PgpError> {
+    let fingerprint = sig.fingerprint();
+    for subpacket in sig.unhashed_subpackets() {
+        if subpacket.tag == 33
+            && subpacket.data.first() == Some(&4)
+            && subpacket.data[1..] != fingerprint[..]
+        {
+            return Err(PgpError::FingerprintMismatch);
+        }
+    }
+    Ok(())
+}
+
+fn has_correct_hashed_subpackets(packet: &[u8]) -> Result<(), PgpError> {
+    if !has_fingerprint_subpacket(packet) {
         return Err(PgpError::MissingFingerprintSubpacket);
     }
 
-    // check that the first subpacket is a fingerprint subpacket
-    if !(packet[6] == 22 && packet[7] == 33 && packet[8] == 4) {
+    // no key has the all-zero fingerprint
+    if packet[9..29].iter().all(|&byte| byte == 0) {
         return Err(PgpError::MissingFingerprintSubpacket);
     }
 
     Ok(())
 }
+
+// Check that the first hashed subpacket is a v4 fingerprint subpacket.
+fn has_fingerprint_subpacket(packet: &[u8]) -> bool {
+    let hashed_len = BigEndian::read_u16(&packet[4..6]) as usize;
+    hashed_len >= 23 && packet[6] == 22 && packet[7] == 33 && packet[8] == 4
+}
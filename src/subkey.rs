@@ -0,0 +1,126 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use byteorder::{BigEndian, ByteOrder};
+use digest::Digest;
+
+#[cfg(feature = "dalek")]
+use ed25519_dalek as dalek;
+
+#[cfg(feature = "dalek")]
+use crate::backend::{Dalek, VerifyBackend};
+use crate::key::{ed25519_key, fingerprint};
+use crate::Base64;
+use crate::{Fingerprint, KeyFlags, PgpSig, Signature};
+
+/// An ed25519 subkey of a `PgpKey`.
+///
+/// Subkeys are read from a key with `PgpKey::subkeys`, and added to a key
+/// with `PgpKey::add_subkey`. Use `PgpKey::verify_subkey_binding` to check
+/// that a subkey was bound to its primary key before trusting signatures
+/// made by the subkey.
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Subkey {
+    packet: Vec<u8>,
+    binding: Option<PgpSig>,
+}
+
+impl Subkey {
+    pub(crate) fn new(packet: Vec<u8>, binding: Option<PgpSig>) -> Subkey {
+        Subkey { packet, binding }
+    }
+
+    pub(crate) fn set_binding(&mut self, binding: PgpSig) {
+        self.binding = Some(binding);
+    }
+
+    /// The public subkey packet (without its binding signature).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.packet[..]
+    }
+
+    /// The ed25519 public key data contained in this subkey.
+    pub fn key_data(&self) -> [u8; 32] {
+        ed25519_key(&self.packet[3..])
+    }
+
+    /// The creation time of this subkey, as a unix timestamp.
+    pub fn created_at(&self) -> u32 {
+        BigEndian::read_u32(&self.packet[4..8])
+    }
+
+    /// The OpenPGP fingerprint of this subkey.
+    pub fn fingerprint(&self) -> Fingerprint {
+        // subkey fingerprints are computed as though the packet were a
+        // public key packet
+        let mut packet = self.packet.clone();
+        packet[0] = 0x99;
+        fingerprint(&packet)
+    }
+
+    /// The subkey binding signature which binds this subkey to its primary
+    /// key, if it has one.
+    pub fn binding_signature(&self) -> Option<&PgpSig> {
+        self.binding.as_ref()
+    }
+
+    /// The key flags from this subkey's binding signature.
+    ///
+    /// If there is no binding signature, or it has no key flags, this
+    /// returns `KeyFlags::NONE`.
+    pub fn key_flags(&self) -> KeyFlags {
+        self.binding
+            .as_ref()
+            .and_then(|binding| {
+                binding
+                    .hashed_subpackets()
+                    .find(|subpacket| subpacket.tag == 27)
+            })
+            .map_or(KeyFlags::NONE, |flags| KeyFlags::from_octets(flags.data))
+    }
+
+    /// Verify data against a signature made by this subkey.
+    ///
+    /// This behaves like `PgpKey::verify`, using the subkey's fingerprint
+    /// and public key data. It does not check that the subkey is bound to
+    /// its primary key.
+    pub fn verify<D, F1, F2>(&self, sig: &PgpSig, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8; 32], &[u8], Signature) -> bool,
+    {
+        if !sig.is_issued_by(&self.fingerprint()) {
+            return false;
+        }
+
+        let key = self.key_data();
+        sig.verify::<D, _, _>(input, |data, signature| verify(&key, data, signature))
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Verify data against a signature made by this subkey, using
+    /// ed25519-dalek.
+    pub fn verify_dalek<D, F>(&self, sig: &PgpSig, input: F) -> bool
+    where
+        D: Digest,
+        F: FnOnce(&mut D),
+    {
+        self.verify::<D, _, _>(sig, input, Dalek::verify)
+    }
+
+    #[cfg(feature = "dalek")]
+    /// Convert this subkey into a dalek VerifyingKey.
+    pub fn to_dalek(&self) -> Result<dalek::VerifyingKey, dalek::SignatureError> {
+        dalek::VerifyingKey::from_bytes(&self.key_data())
+    }
+}
+
+impl Debug for Subkey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subkey")
+            .field("key", &Base64(&self.packet[..]))
+            .field("binding", &self.binding)
+            .finish()
+    }
+}
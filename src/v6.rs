@@ -0,0 +1,366 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use byteorder::{BigEndian, ByteOrder};
+use digest::Digest;
+use typenum::Unsigned;
+
+use crate::packet::*;
+use crate::sig::{find_signature_packet, SubPacketIter};
+use crate::Base64;
+use crate::{HashAlgorithm, PgpError, SigType, SubPacket};
+
+/// A version 6 OpenPGP signature, as defined by RFC 9580.
+///
+/// Version 6 signatures are made with the Ed25519 algorithm (27), have four
+/// octet subpacket lengths, and hash a random salt before the signed data.
+/// They identify the signing key by its 32 byte version 6 fingerprint,
+/// rather than the 20 byte version 4 `Fingerprint`.
+///
+/// This is only available with the `v6` feature. Version 4 signatures, as
+/// made by `PgpSig`, remain the default.
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PgpSigV6 {
+    // A signature packet with a three byte header, which has passed
+    // has_correct_structure.
+    data: Vec<u8>,
+}
+
+impl PgpSigV6 {
+    /// Construct a new version 6 PGP signature.
+    ///
+    /// This is like `PgpSig::new`, except that the fingerprint is a version
+    /// 6 fingerprint, and a salt must be given. The salt should be freshly
+    /// generated random data for each signature, and its length must be
+    /// the one RFC 9580 assigns to the hash algorithm: 16 octets for
+    /// SHA-224, SHA-256 and SHA3-256, 24 for SHA-384, and 32 for SHA-512
+    /// and SHA3-512. The signature has no expiration, and no unhashed
+    /// subpackets.
+    ///
+    /// # Warnings
+    ///
+    /// This will panic if the output size of the digest does not match the
+    /// digest length of the hash algorithm, if the hash algorithm is MD5,
+    /// SHA-1 or RIPEMD-160, which RFC 9580 forbids in version 6
+    /// signatures, if the salt has the wrong length, or if the subpackets
+    /// are too large, as checked by `check_subpacket_sizes`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<D, F>(
+        data: &[u8],
+        fingerprint: [u8; 32],
+        sig_type: SigType,
+        hash_algorithm: HashAlgorithm,
+        unix_time: u32,
+        salt: &[u8],
+        subpackets: &[SubPacket],
+        sign: F,
+    ) -> PgpSigV6
+    where
+        D: Digest,
+        F: Fn(&[u8]) -> [u8; 64],
+    {
+        assert!(D::OutputSize::to_usize() == hash_algorithm.digest_len());
+        assert!(!hash_algorithm.is_weak());
+        assert!(salt.len() == salt_len(hash_algorithm));
+        assert!(
+            PgpSigV6::check_subpacket_sizes(subpackets).is_ok(),
+            "subpackets are too large to fit in a signature packet"
+        );
+
+        let mut hashed_subpackets = vec![];
+
+        // fingerprint
+        write_single_subpacket(&mut hashed_subpackets, 33, |packet| {
+            packet.push(6);
+            packet.extend(&fingerprint[..]);
+        });
+
+        // timestamp
+        write_single_subpacket(&mut hashed_subpackets, 2, |packet| {
+            packet.extend(&bigendian_u32(unix_time))
+        });
+
+        for subpacket in subpackets {
+            let tag = if subpacket.critical {
+                subpacket.tag | 0x80
+            } else {
+                subpacket.tag
+            };
+            write_single_subpacket(&mut hashed_subpackets, tag, |packet| {
+                packet.extend(subpacket.data)
+            });
+        }
+
+        let data = prepare_packet(2, |packet| {
+            packet.push(6); // version number
+            packet.push(sig_type.as_byte()); // signature class
+            packet.push(27); // signing algorithm (Ed25519)
+            packet.push(hash_algorithm.as_byte()); // hash algorithm
+            packet.extend(&bigendian_u32(hashed_subpackets.len() as u32));
+            packet.extend(&hashed_subpackets);
+
+            let hash = {
+                let mut hasher = D::default();
+
+                hasher.process(salt);
+                hasher.process(data);
+                hasher.process(&packet[3..]);
+
+                hasher.process(&[0x06, 0xff]);
+                hasher.process(&bigendian_u32((packet.len() - 3) as u32));
+
+                hasher.fixed_result()
+            };
+
+            // no unhashed subpackets
+            packet.extend(&[0, 0, 0, 0]);
+
+            packet.extend(&hash[0..2]);
+            packet.push(salt.len() as u8);
+            packet.extend(salt);
+
+            packet.extend(&sign(&hash[..])[..]);
+        });
+
+        PgpSigV6 { data }
+    }
+
+    /// Check that a version 6 signature with these hashed subpackets can be
+    /// encoded.
+    ///
+    /// This is like `PgpSig::check_subpacket_sizes`, and returns
+    /// `PgpError::SubpacketTooLarge` if the subpackets, together with the
+    /// fingerprint and timestamp subpackets and the longest salt, would not
+    /// fit in a signature packet. `new` panics in that case.
+    pub fn check_subpacket_sizes(subpackets: &[SubPacket]) -> Result<(), PgpError> {
+        // the fingerprint and timestamp subpackets
+        let hashed_len = subpackets
+            .iter()
+            .map(|subpacket| subpacket_len(subpacket.data.len()))
+            .fold(35 + 6, usize::saturating_add);
+        // the version, type and algorithms, the two subpacket lengths, the
+        // hash prefix, a salt of at most 32 octets and its length, and the
+        // signature
+        let body_len = hashed_len.saturating_add(4 + 4 + 4 + 2 + 1 + 32 + 64);
+        if body_len > u16::MAX as usize {
+            return Err(PgpError::SubpacketTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Parse a version 6 OpenPGP signature from binary data.
+    ///
+    /// This must be an Ed25519 signature using a supported hash algorithm,
+    /// with a version 6 issuer fingerprint subpacket in its hashed section.
+    /// The data must contain exactly one signature packet.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PgpSigV6, PgpError> {
+        let (data, rest) = find_signature_packet(bytes)?;
+        if !rest.is_empty() {
+            return Err(PgpError::TrailingData);
+        }
+        has_correct_structure(&data[3..])?;
+        let sig = PgpSigV6 { data };
+        if sig.hashed_fingerprint().is_none() {
+            return Err(PgpError::MissingFingerprintSubpacket);
+        }
+        Ok(sig)
+    }
+
+    /// Get the binary representation of this signature.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the type of this signature, or an error if the signature type
+    /// byte is not a known signature type.
+    pub fn try_sig_type(&self) -> Result<SigType, PgpError> {
+        SigType::try_from(self.data[4])
+    }
+
+    /// Get the hash algorithm used to produce this signature.
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::try_from(self.data[6]).expect("hash algorithm is checked when parsed")
+    }
+
+    /// Get the version 6 fingerprint of the key which made this signature.
+    ///
+    /// This is read from the issuer fingerprint subpacket in the hashed
+    /// section.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        self.hashed_fingerprint()
+            .expect("fingerprint subpacket is checked when parsed")
+    }
+
+    /// Get the signature creation time, as a unix timestamp.
+    pub fn timestamp(&self) -> Option<u32> {
+        self.hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 2 && subpacket.data.len() == 4)
+            .map(|subpacket| BigEndian::read_u32(subpacket.data))
+    }
+
+    /// Get the portion of this signature hashed into the signed data, after
+    /// the salt and the data itself.
+    ///
+    /// This is bytes `3..(11 + n)` of `as_bytes`, where `n` is the four
+    /// octet hashed subpacket length stored in bytes `7..11`.
+    pub fn hashed_section(&self) -> &[u8] {
+        &self.data[3..(11 + self.hashed_len())]
+    }
+
+    /// Iterate over the subpackets in the hashed section of this signature.
+    ///
+    /// Iteration stops early if a subpacket is truncated or malformed.
+    pub fn hashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'_>> {
+        SubPacketIter {
+            data: &self.data[11..(11 + self.hashed_len())],
+        }
+    }
+
+    /// Iterate over the subpackets in the unhashed section of this
+    /// signature.
+    ///
+    /// Iteration stops early if a subpacket is truncated or malformed.
+    pub fn unhashed_subpackets(&self) -> impl Iterator<Item = SubPacket<'_>> {
+        let init = 15 + self.hashed_len();
+        SubPacketIter {
+            data: &self.data[init..(init + self.unhashed_len())],
+        }
+    }
+
+    /// The first two bytes of the digest, as stored in this signature.
+    pub fn hash_prefix(&self) -> [u8; 2] {
+        let init = 15 + self.hashed_len() + self.unhashed_len();
+        [self.data[init], self.data[init + 1]]
+    }
+
+    /// The salt hashed before the signed data.
+    pub fn salt(&self) -> &[u8] {
+        let init = 17 + self.hashed_len() + self.unhashed_len();
+        &self.data[(init + 1)..][..self.data[init] as usize]
+    }
+
+    /// Get the actual Ed25519 signature contained.
+    pub fn signature(&self) -> [u8; 64] {
+        let mut signature = [0; 64];
+        signature.copy_from_slice(&self.data[(self.data.len() - 64)..]);
+        signature
+    }
+
+    /// Verify data against this signature.
+    ///
+    /// This is like `PgpSig::verify`: the input function hashes the signed
+    /// data, after the salt has been hashed.
+    pub fn verify<D, F1, F2>(&self, input: F1, verify: F2) -> bool
+    where
+        D: Digest,
+        F1: FnOnce(&mut D),
+        F2: FnOnce(&[u8], [u8; 64]) -> bool,
+    {
+        if D::OutputSize::to_usize() != self.hash_algorithm().digest_len() {
+            return false;
+        }
+
+        let hash = {
+            let mut hasher = D::default();
+
+            hasher.process(self.salt());
+            input(&mut hasher);
+
+            let hashed_section = self.hashed_section();
+            hasher.process(hashed_section);
+
+            hasher.process(&[0x06, 0xff]);
+            hasher.process(&bigendian_u32(hashed_section.len() as u32));
+
+            hasher.fixed_result()
+        };
+
+        hash[0..2] == self.hash_prefix() && verify(&hash[..], self.signature())
+    }
+
+    fn hashed_len(&self) -> usize {
+        BigEndian::read_u32(&self.data[7..11]) as usize
+    }
+
+    fn unhashed_len(&self) -> usize {
+        let init = 11 + self.hashed_len();
+        BigEndian::read_u32(&self.data[init..(init + 4)]) as usize
+    }
+
+    fn hashed_fingerprint(&self) -> Option<[u8; 32]> {
+        let subpacket = self
+            .hashed_subpackets()
+            .find(|subpacket| subpacket.tag == 33 && subpacket.data.len() == 33)?;
+        if subpacket.data[0] != 6 {
+            return None;
+        }
+        let mut fingerprint = [0; 32];
+        fingerprint.copy_from_slice(&subpacket.data[1..]);
+        Some(fingerprint)
+    }
+}
+
+impl Debug for PgpSigV6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PgpSigV6")
+            .field("sig", &Base64(&self.data[..]))
+            .finish()
+    }
+}
+
+// The salt length RFC 9580 assigns to each hash algorithm. The weak
+// algorithms are forbidden in version 6 signatures, so have none.
+fn salt_len(hash_algorithm: HashAlgorithm) -> usize {
+    match hash_algorithm {
+        HashAlgorithm::Md5 | HashAlgorithm::Sha1 | HashAlgorithm::Ripemd160 => 0,
+        HashAlgorithm::Sha224 | HashAlgorithm::Sha256 | HashAlgorithm::Sha3_256 => 16,
+        HashAlgorithm::Sha384 => 24,
+        HashAlgorithm::Sha512 | HashAlgorithm::Sha3_512 => 32,
+    }
+}
+
+fn has_correct_structure(packet: &[u8]) -> Result<(), PgpError> {
+    if packet.len() < 8 {
+        return Err(PgpError::UnsupportedSignaturePacket);
+    }
+
+    if packet[0] != 6 {
+        return Err(PgpError::UnexpectedSignatureVersion(packet[0]));
+    }
+
+    if packet[2] != 27 {
+        return Err(PgpError::UnexpectedPublicKeyAlgorithm(packet[2]));
+    }
+
+    let hash_algorithm = HashAlgorithm::try_from(packet[3])?;
+    if hash_algorithm.is_weak() {
+        return Err(PgpError::WeakHashAlgorithm(packet[3]));
+    }
+
+    // the hashed subpackets and the unhashed subpacket length
+    let hashed_len = BigEndian::read_u32(&packet[4..8]) as usize;
+    let rest = &packet[8..];
+    if rest.len() < 4 || hashed_len > rest.len() - 4 {
+        return Err(PgpError::HashedSubpacketLengthOverflow);
+    }
+
+    // the unhashed subpackets, the hash prefix and the salt length
+    let rest = &rest[hashed_len..];
+    let unhashed_len = BigEndian::read_u32(&rest[..4]) as usize;
+    let rest = &rest[4..];
+    if rest.len() < 3 || unhashed_len > rest.len() - 3 {
+        return Err(PgpError::UnsupportedSignaturePacket);
+    }
+
+    let rest = &rest[(unhashed_len + 2)..];
+    let salt = rest[0] as usize;
+    if salt != salt_len(hash_algorithm) {
+        return Err(PgpError::InvalidSalt);
+    }
+
+    match rest[1..].len().checked_sub(salt) {
+        Some(64) => Ok(()),
+        Some(len) if len > 64 => Err(PgpError::TrailingDataAfterSignature),
+        _ => Err(PgpError::UnsupportedSignaturePacket),
+    }
+}
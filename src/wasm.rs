@@ -0,0 +1,135 @@
+//! Signature verification for WebAssembly.
+//!
+//! With the `wasm` feature, this module exports `verify_detached` to
+//! JavaScript through `wasm-bindgen`. Build it for the browser with:
+//!
+//! ```text
+//! cargo build --target wasm32-unknown-unknown --features wasm
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/debug/pbp_pkgx.wasm --out-dir pkg
+//! ```
+//!
+//! Only verification is exported. Verifying does not use the filesystem,
+//! and reads the current time, to check for expired signatures, from
+//! JavaScript's `Date.now` rather than `std::time`. The `getrandom`
+//! feature does not build for `wasm32-unknown-unknown`, so keys should be
+//! generated elsewhere.
+use digest::Digest;
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+use wasm_bindgen::prelude::*;
+
+use crate::backend::Dalek;
+use crate::sha3::{Sha3_256, Sha3_512};
+use crate::sig::hash_canonical_text;
+use crate::{HashAlgorithm, PgpKey, PgpSig, VerifyBackend};
+
+/// Verify data against a detached signature at the current time.
+///
+/// This is `verify_detached_at` with the current unix time.
+#[wasm_bindgen]
+pub fn verify_detached(sig_bytes: &[u8], data: &[u8], pubkey_bytes: &[u8]) -> bool {
+    verify_detached_at(sig_bytes, data, pubkey_bytes, unix_time())
+}
+
+/// Verify data against a detached signature at the unix time `now`.
+///
+/// The signature is a binary signature packet, as returned by
+/// `PgpSig::as_bytes`. The public key is either a binary OpenPGP public
+/// key, in which case the signature must have been made by that key, the
+/// key must not have expired, and no valid key revocation signature may be
+/// appended to it, or the 32 bytes of an ed25519
+/// public key. A raw ed25519 key has no creation time, so its fingerprint
+/// cannot be computed and the signature's issuer is not checked against
+/// it; only the signature itself is. Pass the full key when the issuer
+/// matters. Text document signatures are checked against the
+/// canonicalized text.
+///
+/// As with `PgpSig::verify_detailed`, the signature must not have expired
+/// at `now`, must not have a critical subpacket outside
+/// `PgpSig::KNOWN_SUBPACKETS`, and must use one of
+/// `PgpSig::ALLOWED_HASH_ALGORITHMS`.
+///
+/// Returns false if the signature or key cannot be parsed, or if any of
+/// these checks fail.
+#[wasm_bindgen]
+pub fn verify_detached_at(sig_bytes: &[u8], data: &[u8], pubkey_bytes: &[u8], now: u32) -> bool {
+    let sig = match PgpSig::from_bytes(sig_bytes) {
+        Ok(sig) => sig,
+        Err(_) => return false,
+    };
+    if sig.is_expired(now)
+        || sig
+            .check_critical_subpackets(PgpSig::KNOWN_SUBPACKETS)
+            .is_err()
+    {
+        return false;
+    }
+    match sig.hash_algorithm() {
+        HashAlgorithm::Sha256 => verify::<Sha256>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Sha384 => verify::<Sha384>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Sha512 => verify::<Sha512>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Sha224 => verify::<Sha224>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Sha3_256 => verify::<Sha3_256>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Sha3_512 => verify::<Sha3_512>(&sig, data, pubkey_bytes, now),
+        HashAlgorithm::Md5 | HashAlgorithm::Sha1 | HashAlgorithm::Ripemd160 => false,
+    }
+}
+
+fn verify<D: Digest>(sig: &PgpSig, data: &[u8], pubkey_bytes: &[u8], now: u32) -> bool {
+    let input = |hasher: &mut D| {
+        if sig.is_text() {
+            hash_canonical_text(hasher, data)
+        } else {
+            hasher.process(data)
+        }
+    };
+    if let Ok(key) = <&[u8; 32]>::try_from(pubkey_bytes) {
+        return match dalek::VerifyingKey::from_bytes(key) {
+            Ok(key) => sig.verify_dalek::<D, Sha512, _>(&key, input),
+            Err(_) => false,
+        };
+    }
+    match PgpKey::from_bytes(pubkey_bytes) {
+        Ok(key) if key.expires_at().is_none_or(|expires_at| now < expires_at) => {
+            !is_revoked(&key) && key.verify_dalek::<D, _>(sig, input)
+        }
+        _ => false,
+    }
+}
+
+// Whether the key data contains a valid revocation of the key, made with
+// any hash algorithm a signature can be verified with here.
+fn is_revoked(key: &PgpKey) -> bool {
+    key.revocation_signatures()
+        .any(|sig| match sig.hash_algorithm() {
+            HashAlgorithm::Sha256 => key.is_revoked_by::<Sha256, _>(&sig, Dalek::verify),
+            HashAlgorithm::Sha384 => key.is_revoked_by::<Sha384, _>(&sig, Dalek::verify),
+            HashAlgorithm::Sha512 => key.is_revoked_by::<Sha512, _>(&sig, Dalek::verify),
+            HashAlgorithm::Sha224 => key.is_revoked_by::<Sha224, _>(&sig, Dalek::verify),
+            HashAlgorithm::Sha3_256 => key.is_revoked_by::<Sha3_256, _>(&sig, Dalek::verify),
+            HashAlgorithm::Sha3_512 => key.is_revoked_by::<Sha3_512, _>(&sig, Dalek::verify),
+            HashAlgorithm::Md5 | HashAlgorithm::Sha1 | HashAlgorithm::Ripemd160 => false,
+        })
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+}
+
+// std::time panics on wasm32-unknown-unknown, so the time is read from
+// JavaScript there.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn unix_time() -> u32 {
+    (date_now() / 1000.0) as u32
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn unix_time() -> u32 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs().min(u32::MAX as u64) as u32)
+}
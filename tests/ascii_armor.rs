@@ -0,0 +1,240 @@
+extern crate pbp_pkgx;
+
+use pbp_pkgx::{PgpError, PgpSig};
+
+// The signature in examples/props/sig.txt, with its packet header
+// rewritten to the three byte form this library uses.
+const SIG: &[u8] = &[
+    0x89, 0x00, 0x75, 0x04, 0x00, 0x16, 0x08, 0x00, 0x1d, 0x16, 0x21, 0x04, 0xb6, 0x35, 0xb5, 0xfb,
+    0x5e, 0xd9, 0xea, 0x73, 0xf9, 0x53, 0x5f, 0x0a, 0x1c, 0xc7, 0x03, 0x10, 0xbe, 0x39, 0x12, 0xd5,
+    0x05, 0x02, 0x5a, 0x20, 0xbe, 0x3e, 0x00, 0x0a, 0x09, 0x10, 0x1c, 0xc7, 0x03, 0x10, 0xbe, 0x39,
+    0x12, 0xd5, 0x8e, 0x48, 0x01, 0x00, 0xcd, 0x93, 0x80, 0x6e, 0xe2, 0xc0, 0x80, 0x1d, 0x39, 0x6f,
+    0x51, 0x24, 0xbd, 0x8a, 0x83, 0xa8, 0xf7, 0x25, 0x83, 0xa8, 0x8b, 0xe5, 0x88, 0x4d, 0x5a, 0x06,
+    0xf6, 0x5e, 0x64, 0x2c, 0xc3, 0x2e, 0x01, 0x00, 0x81, 0x04, 0xac, 0x42, 0xd3, 0xfc, 0x46, 0xaa,
+    0xbf, 0x7f, 0xe4, 0x87, 0xc4, 0xb5, 0x76, 0x72, 0x06, 0x69, 0x14, 0xb7, 0x0a, 0x10, 0x88, 0x34,
+    0xa1, 0xd8, 0xbc, 0x53, 0x1b, 0xf8, 0x58, 0x09,
+];
+
+#[test]
+fn armor_checksum_matches_gnupg() {
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+    let armored = sig.to_string();
+
+    // the checksum `gpg --enarmor` produces for the same bytes
+    let cksum_line = armored.lines().rev().nth(1).unwrap();
+    assert_eq!(cksum_line, "=GnXU");
+}
+
+#[test]
+fn armor_checksum_is_validated() {
+    let armored = include_str!("../examples/props/sig.txt");
+    let sig = PgpSig::from_ascii_armor_strict(armored).unwrap();
+    assert_eq!(sig, PgpSig::from_bytes(SIG).unwrap());
+
+    let corrupted = armored.replace("=uBdw", "=uBdx");
+    match PgpSig::from_ascii_armor(&corrupted) {
+        Err(PgpError::ChecksumMismatch) => {}
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+
+    let missing: String = armored
+        .lines()
+        .filter(|line| !line.starts_with('='))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    assert_eq!(PgpSig::from_ascii_armor(&missing).unwrap(), sig);
+    assert!(PgpSig::from_ascii_armor_strict(&missing).is_err());
+}
+
+#[test]
+fn armor_headers() {
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+    let armored = sig.to_armored_string_with_headers(&[("Version", "pbp"), ("Comment", "pkgx")]);
+
+    let lines: Vec<&str> = armored.lines().collect();
+    assert_eq!(
+        lines[..4],
+        [
+            "-----BEGIN PGP SIGNATURE-----",
+            "Version: pbp",
+            "Comment: pkgx",
+            ""
+        ]
+    );
+    assert_eq!(PgpSig::from_ascii_armor_strict(&armored).unwrap(), sig);
+}
+
+#[test]
+fn invalid_base64_is_the_error_source() {
+    use std::error::Error;
+
+    let armored = include_str!("../examples/props/sig.txt");
+    let first_body_line = armored.lines().nth(2).unwrap();
+    let corrupted = armored.replacen(first_body_line, "!!!!", 1);
+    let err = PgpSig::from_ascii_armor(&corrupted).unwrap_err();
+    assert!(matches!(err, PgpError::InvalidBase64(_)));
+    assert!(err.source().is_some());
+
+    let boxed: Box<dyn Error> = Box::new(err);
+    assert!(boxed.to_string().starts_with("Invalid base64"));
+}
+
+#[test]
+fn canonical_armor() {
+    let gnupg = include_str!("../examples/props/sig.txt");
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+
+    // CRLF line endings and a comment header
+    let crlf = sig
+        .to_armored_string_with_headers(&[("Comment", "pkgx")])
+        .replace('\n', "\r\n");
+
+    // 40 character lines, without a checksum
+    let armored = sig.to_string();
+    let body: String = armored
+        .lines()
+        .skip(2)
+        .take_while(|line| !line.starts_with('='))
+        .collect();
+    let mut narrow = String::from("-----BEGIN PGP SIGNATURE-----\n\n");
+    for line in body.as_bytes().chunks(40) {
+        narrow.push_str(std::str::from_utf8(line).unwrap());
+        narrow.push('\n');
+    }
+    narrow.push_str("-----END PGP SIGNATURE-----\n");
+
+    let canonical: Vec<String> = [gnupg, &crlf, &narrow]
+        .iter()
+        .map(|armor| {
+            PgpSig::from_ascii_armor(armor)
+                .unwrap()
+                .to_canonical_armor()
+        })
+        .collect();
+    assert_eq!(canonical[0], canonical[1]);
+    assert_eq!(canonical[0], canonical[2]);
+
+    let lines: Vec<&str> = canonical[0].lines().collect();
+    assert_eq!(lines[..2], ["-----BEGIN PGP SIGNATURE-----", ""]);
+    assert_eq!(lines[2].len(), 64);
+    assert_eq!(lines[lines.len() - 2], "=GnXU");
+    assert!(!canonical[0].contains('\r'));
+    assert_eq!(PgpSig::from_ascii_armor_strict(&canonical[0]).unwrap(), sig);
+}
+
+#[test]
+fn armor_line_width() {
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+    assert_eq!(sig.to_string(), sig.to_armored_string_with_width(64));
+
+    for width in [1, 40, 64, 76] {
+        let armored = sig.to_armored_string_with_width(width);
+        let lines: Vec<&str> = armored.lines().skip(2).collect();
+        let body = &lines[..lines.len() - 2];
+        assert!(body.iter().all(|line| line.len() <= width));
+        assert_eq!(body[0].len(), width);
+        assert_eq!(PgpSig::from_ascii_armor_strict(&armored).unwrap(), sig);
+    }
+}
+
+#[test]
+fn armor_options() {
+    use pbp_pkgx::ascii_armor::ArmorOptions;
+
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+    assert_eq!(
+        sig.to_string(),
+        sig.to_armored_string_with_options(&ArmorOptions::default())
+    );
+
+    let armored = sig.to_armored_string_with_options(&ArmorOptions {
+        headers: &[("Comment", "no checksum")],
+        line_width: 76,
+        checksum: false,
+    });
+    assert!(armored.contains("\nComment: no checksum\n"));
+    assert!(!armored.lines().any(|line| line.starts_with('=')));
+    assert!(armored.ends_with("\n-----END PGP SIGNATURE-----\n"));
+    assert_eq!(PgpSig::from_ascii_armor(&armored).unwrap(), sig);
+    assert!(PgpSig::from_ascii_armor_strict(&armored).is_err());
+}
+
+#[test]
+fn compare_with_representations() {
+    use pbp_pkgx::ascii_armor::ArmorOptions;
+
+    let sig = PgpSig::from_bytes(SIG).unwrap();
+    assert_eq!(sig, *SIG);
+    assert_eq!(sig, SIG);
+    assert!(sig != SIG[1..]);
+
+    let armored = sig.to_string();
+    assert_eq!(sig, *armored);
+    assert_eq!(sig, armored.as_str());
+    assert_eq!(sig, sig.to_armored_string_with_width(76).as_str());
+    assert_eq!(sig, armored.replace('\n', "\r\n").as_str());
+    assert!(sig != armored.replace("SIGNATURE", "MESSAGE").as_str());
+    assert!(sig != "");
+
+    let with_comment = sig.to_armored_string_with_options(&ArmorOptions {
+        headers: &[("Comment", "no checksum")],
+        line_width: 64,
+        checksum: false,
+    });
+    assert_eq!(sig, with_comment.as_str());
+}
+
+#[test]
+fn multiple_armored_blocks() {
+    use pbp_pkgx::ascii_armor::armored_blocks;
+    use pbp_pkgx::PgpKey;
+
+    let key = include_str!("../examples/props/key.txt");
+    let sig = include_str!("../examples/props/sig.txt");
+    let text = [
+        "name: props\n",
+        key,
+        "\n-----BEGIN PGP SIGNATURE-----\nnever ended\n",
+        sig,
+        "-----BEGIN PGP MESSAGE-----\n\nAAAA\n-----END PGP SIGNATURE-----\n",
+        "-----END PGP SIGNATURE-----\n",
+        "-----BEGIN PGP SIGNATURE-----\n\n!!!!\n-----END PGP SIGNATURE-----\n",
+    ]
+    .concat();
+
+    let blocks: Vec<(String, Vec<u8>)> = armored_blocks(&text).collect();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].0, "PGP PUBLIC KEY BLOCK");
+    assert_eq!(
+        PgpKey::from_bytes(&blocks[0].1).unwrap(),
+        PgpKey::from_ascii_armor(key).unwrap()
+    );
+    assert_eq!(blocks[1].0, "PGP SIGNATURE");
+    assert_eq!(
+        PgpSig::from_bytes(&blocks[1].1).unwrap(),
+        PgpSig::from_bytes(SIG).unwrap()
+    );
+
+    assert_eq!(armored_blocks("").count(), 0);
+    assert_eq!(armored_blocks("-----END PGP SIGNATURE-----").count(), 0);
+}
+
+#[test]
+fn peek_label() {
+    use pbp_pkgx::ascii_armor::peek_armor_label;
+
+    let key = include_str!("../examples/props/key.txt");
+    let sig = include_str!("../examples/props/sig.txt");
+    assert_eq!(peek_armor_label(key), Some("PGP PUBLIC KEY BLOCK"));
+    assert_eq!(
+        peek_armor_label(&format!("\n  {}", sig)),
+        Some("PGP SIGNATURE")
+    );
+    assert_eq!(peek_armor_label("-----BEGIN PGP SIGNATURE"), None);
+    assert_eq!(peek_armor_label("mDMEWh36qhYJKwYBBAHaRw8BAQdA"), None);
+
+    // a key pasted where a signature was expected
+    let err = PgpSig::from_ascii_armor(key).unwrap_err();
+    assert!(matches!(err, PgpError::InvalidAsciiArmor));
+    assert_ne!(peek_armor_label(key), Some("PGP SIGNATURE"));
+}
@@ -0,0 +1,110 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use common::sign;
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpSig, SigType, SubPacket};
+use sha2::{Sha256, Sha512};
+
+#[test]
+fn builder_matches_new() {
+    let fingerprint = Fingerprint([0x42; 20]);
+    let subpacket = SubPacket {
+        tag: 23,
+        data: &[0x80],
+        critical: false,
+    };
+
+    let built = PgpSig::builder()
+        .sig_type(SigType::Standalone)
+        .hash_algorithm(HashAlgorithm::Sha512)
+        .timestamp(1_700_000_000)
+        .expiration(3600)
+        .subpacket(subpacket)
+        .sign::<Sha512, _>(b"data", fingerprint, sign);
+    let new = PgpSig::new::<Sha512, _>(
+        b"data",
+        fingerprint,
+        SigType::Standalone,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        Some(3600),
+        &[subpacket],
+        sign,
+    );
+    assert_eq!(built, new);
+
+    let text = PgpSig::builder()
+        .sig_type(SigType::TextDocument)
+        .hash_algorithm(HashAlgorithm::Sha512)
+        .sign::<Sha512, _>(b"line \n", fingerprint, sign);
+    let new_text = PgpSig::new_text::<Sha512, _>(
+        b"line \n",
+        fingerprint,
+        HashAlgorithm::Sha512,
+        0,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(text, new_text);
+}
+
+#[test]
+fn builder_defaults() {
+    let sig = PgpSig::builder().sign::<Sha256, _>(b"data", Fingerprint([0x42; 20]), |hash| {
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(hash);
+        signature
+    });
+    assert_eq!(sig.sig_type(), SigType::BinaryDocument);
+    assert_eq!(sig.hash_algorithm(), HashAlgorithm::Sha256);
+    assert_eq!(sig.timestamp(), Some(0));
+    assert_eq!(sig.expiration(), None);
+}
+
+#[test]
+fn resign() {
+    use pbp_pkgx::{KeyId, Notation};
+
+    let notation = Notation::text("release@pkgx.dev", "1.0")
+        .to_subpacket()
+        .unwrap();
+    let old = PgpSig::builder()
+        .sig_type(SigType::TextDocument)
+        .hash_algorithm(HashAlgorithm::Sha512)
+        .timestamp(1_600_000_000)
+        .expiration(86_400)
+        .subpacket(SubPacket {
+            tag: Notation::TAG,
+            data: &notation,
+            critical: true,
+        })
+        .sign::<Sha512, _>(b"artifact\n", Fingerprint([0x42; 20]), sign);
+
+    let new = old.resign::<Sha512, _>(b"artifact\n", Fingerprint([0x43; 20]), 1_700_000_000, sign);
+    assert_eq!(new.fingerprint(), Fingerprint([0x43; 20]));
+    assert_eq!(new.issuer_key_id(), Some(KeyId([0x43; 8])));
+    assert_eq!(new.timestamp(), Some(1_700_000_000));
+    assert_eq!(new.expiration(), Some(86_400));
+    assert_eq!(new.sig_type(), SigType::TextDocument);
+    assert_eq!(new.hash_algorithm(), HashAlgorithm::Sha512);
+    assert_eq!(new.notations(), old.notations());
+    assert!(new
+        .hashed_subpackets()
+        .any(|subpacket| subpacket.tag == Notation::TAG && subpacket.critical));
+
+    // the old fingerprint and timestamp are replaced, not duplicated
+    let tags: Vec<u8> = new
+        .hashed_subpackets()
+        .map(|subpacket| subpacket.tag)
+        .collect();
+    assert_eq!(tags, [33, 2, 3, Notation::TAG]);
+
+    assert!(
+        new.verify_text::<Sha512, _>(b"artifact\r\n", |hash, signature| {
+            hash == &signature[..]
+        })
+    );
+}
@@ -0,0 +1,81 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use common::{sign, verify};
+use pbp_pkgx::cleartext;
+use pbp_pkgx::{Fingerprint, HashAlgorithm, SigType};
+use sha2::Sha512;
+
+const TEXT: &str = "pkgx.yaml:\n- name: pbp\n-----BEGIN PGP SIGNATURE-----\n\nversion: 1  \n";
+
+#[test]
+fn cleartext_round_trip() {
+    let document = cleartext::sign::<Sha512, _>(
+        TEXT,
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        sign,
+    );
+    assert!(document.starts_with("-----BEGIN PGP SIGNED MESSAGE-----\nHash: SHA512\n\n"));
+    assert!(document.contains("\n- - name: pbp\n- -----BEGIN PGP SIGNATURE-----\n"));
+
+    let (text, sig) = cleartext::parse(&document).unwrap();
+    assert_eq!(
+        text,
+        "pkgx.yaml:\n- name: pbp\n-----BEGIN PGP SIGNATURE-----\n\nversion: 1\n"
+    );
+    assert_eq!(sig.sig_type(), SigType::TextDocument);
+    assert!(cleartext::verify::<Sha512, _>(&text, &sig, verify));
+
+    // line endings and trailing whitespace are not signed
+    assert!(cleartext::verify::<Sha512, _>(
+        &TEXT.replace('\n', "\r\n"),
+        &sig,
+        verify
+    ));
+    assert!(cleartext::verify::<Sha512, _>(
+        &TEXT.replace('\n', "\r"),
+        &sig,
+        verify
+    ));
+    assert!(!cleartext::verify::<Sha512, _>("version: 2", &sig, verify));
+
+    // the document's line endings don't matter either
+    let (crlf_text, _) = cleartext::parse(&document.replace('\n', "\r\n")).unwrap();
+    assert_eq!(crlf_text, text);
+
+    let wrong_hash = document.replace("Hash: SHA512", "Hash: SHA256");
+    assert!(cleartext::parse(&wrong_hash).is_err());
+}
+
+#[test]
+fn cleartext_line_endings_at_end() {
+    for text in ["a", "a\n", "a\n\n", "a\n\n\n", "a\r\n\r\n", "", "\n"] {
+        let document = cleartext::sign::<Sha512, _>(
+            text,
+            Fingerprint([0x42; 20]),
+            HashAlgorithm::Sha512,
+            1_700_000_000,
+            sign,
+        );
+        let (parsed, sig) = cleartext::parse(&document).unwrap();
+        assert_eq!(parsed, text.replace("\r\n", "\n"));
+        assert!(cleartext::verify::<Sha512, _>(&parsed, &sig, verify));
+        assert!(cleartext::verify::<Sha512, _>(text, &sig, verify));
+    }
+
+    // the number of line endings at the end is signed
+    let document = cleartext::sign::<Sha512, _>(
+        "a\n\n",
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        sign,
+    );
+    let (_, sig) = cleartext::parse(&document).unwrap();
+    assert!(!cleartext::verify::<Sha512, _>("a\n", &sig, verify));
+    assert!(!cleartext::verify::<Sha512, _>("a\n\n\n", &sig, verify));
+}
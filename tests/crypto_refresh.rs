@@ -0,0 +1,86 @@
+#![cfg(feature = "crypto-refresh")]
+extern crate pbp_pkgx;
+extern crate sha1;
+extern crate sha2;
+
+use pbp_pkgx::packet::{bigendian_u32, prepare_packet, write_single_subpacket, write_subpackets};
+use pbp_pkgx::{HashAlgorithm, PgpError, PgpKey, PgpSig};
+use sha2::{Digest, Sha256};
+
+// A version 4 Ed25519 (algorithm 27) public key packet, as RFC 9580
+// defines it: the key octets follow the algorithm, with no curve OID or
+// MPI framing.
+fn ed25519_key_packet() -> Vec<u8> {
+    prepare_packet(6, |packet| {
+        packet.push(4);
+        packet.extend(&bigendian_u32(1_700_000_000));
+        packet.push(27);
+        packet.extend(&[0x42; 32]);
+    })
+}
+
+// A binary document signature by that key, whose 64 signature octets are
+// the SHA-256 digest twice.
+fn ed25519_signature(key: &PgpKey, data: &[u8]) -> Vec<u8> {
+    let fingerprint = key.fingerprint();
+    prepare_packet(2, |packet| {
+        packet.extend(&[4, 0x00, 27, HashAlgorithm::Sha256.as_byte()]);
+        write_subpackets(packet, |subpackets| {
+            write_single_subpacket(subpackets, 33, |subpacket| {
+                subpacket.push(4);
+                subpacket.extend(&fingerprint[..]);
+            });
+            write_single_subpacket(subpackets, 2, |subpacket| {
+                subpacket.extend(&bigendian_u32(1_700_000_000))
+            });
+        });
+
+        let mut hasher = Sha256::default();
+        hasher.input(data);
+        hasher.input(&packet[3..]);
+        hasher.input(&[0x04, 0xff]);
+        hasher.input(&bigendian_u32((packet.len() - 3) as u32));
+        let hash = hasher.result();
+
+        write_subpackets(packet, |_| {});
+        packet.extend(&hash[0..2]);
+        packet.extend(&hash[..]);
+        packet.extend(&hash[..]);
+    })
+}
+
+#[test]
+fn ed25519_key() {
+    let packet = ed25519_key_packet();
+    let key = PgpKey::from_bytes(&packet).unwrap();
+    assert_eq!(key.key_data(), [0x42; 32]);
+    assert_eq!(key.created_at(), 1_700_000_000);
+
+    let mut sha1 = sha1::Sha1::new();
+    sha1.update(&packet);
+    assert_eq!(key.fingerprint().0, sha1.digest().bytes());
+}
+
+#[test]
+fn ed25519_signature_verifies() {
+    let key = PgpKey::from_bytes(&ed25519_key_packet()).unwrap();
+    let bytes = ed25519_signature(&key, b"data");
+
+    let sig = PgpSig::from_bytes(&bytes).unwrap();
+    assert_eq!(sig.public_key_algorithm(), 27);
+    assert_eq!(sig.fingerprint(), key.fingerprint());
+    assert_eq!(sig.signature()[..32], sig.signature()[32..]);
+
+    let verify = |key: &[u8; 32], hash: &[u8], signature: [u8; 64]| {
+        key == &[0x42; 32] && hash == &signature[..32]
+    };
+    assert!(key.verify::<Sha256, _, _>(&sig, |hasher| hasher.input(b"data"), verify));
+    assert!(!key.verify::<Sha256, _, _>(&sig, |hasher| hasher.input(b"other"), verify));
+
+    // the signature is exactly 64 octets
+    let mut long = bytes.clone();
+    long[2] += 1;
+    long.push(0);
+    let err = PgpSig::from_bytes(&long).unwrap_err();
+    assert!(matches!(err, PgpError::TrailingDataAfterSignature));
+}
@@ -0,0 +1,55 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use std::env;
+use std::fs;
+
+use common::{sign, verify};
+use pbp_pkgx::detached::{sign_file, verify_file};
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpError};
+use sha2::Sha512;
+
+#[test]
+fn detached_file_signature() {
+    let dir = env::temp_dir().join(format!("pbp-detached-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let data_path = dir.join("release.tar");
+    let sig_path = dir.join("release.tar.asc");
+
+    // larger than a single chunk
+    let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+    fs::write(&data_path, &data).unwrap();
+
+    let sig = sign_file::<Sha512, _, _>(
+        &data_path,
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        sign,
+    )
+    .unwrap();
+    fs::write(&sig_path, sig.to_string()).unwrap();
+    assert!(verify_file::<Sha512, _, _, _>(&data_path, &sig_path, verify).unwrap());
+
+    fs::write(&sig_path, sig.as_bytes()).unwrap();
+    assert!(verify_file::<Sha512, _, _, _>(&data_path, &sig_path, verify).unwrap());
+
+    fs::write(&data_path, &data[1..]).unwrap();
+    assert!(!verify_file::<Sha512, _, _, _>(&data_path, &sig_path, verify).unwrap());
+
+    assert!(verify_file::<Sha512, _, _, _>(dir.join("missing"), &sig_path, verify).is_err());
+
+    // reading a directory fails after it is opened; nothing is signed
+    let result = sign_file::<Sha512, _, _>(
+        &dir,
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        |_| unreachable!(),
+    );
+    assert!(matches!(result, Err(PgpError::Io(_))));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
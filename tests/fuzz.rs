@@ -0,0 +1,125 @@
+extern crate pbp_pkgx;
+extern crate rand;
+extern crate sha2;
+
+use pbp_pkgx::{Fingerprint, HashAlgorithm, Notation, PgpKey, PgpSig, SigType, SubPacket};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+// Parsing untrusted data must never panic. These tests parse random
+// mutations of valid signatures and keys, and exercise every accessor on
+// whatever parses successfully.
+
+fn mutate(rng: &mut StdRng, valid: &[u8]) -> Vec<u8> {
+    let mut bytes = valid.to_vec();
+    for _ in 0..rng.gen_range(1, 4) {
+        match rng.gen_range(0, 5) {
+            0 if !bytes.is_empty() => {
+                let i = rng.gen_range(0, bytes.len());
+                bytes[i] = rng.gen();
+            }
+            1 if !bytes.is_empty() => {
+                let i = rng.gen_range(0, bytes.len());
+                bytes[i] ^= 1 << rng.gen_range(0, 8);
+            }
+            2 => {
+                let len = rng.gen_range(0, bytes.len() + 1);
+                bytes.truncate(len);
+            }
+            3 => {
+                let i = rng.gen_range(0, bytes.len() + 1);
+                bytes.insert(i, rng.gen());
+            }
+            _ => {
+                let len = rng.gen_range(0, 200);
+                bytes = (0..len).map(|_| rng.gen()).collect();
+            }
+        }
+    }
+    bytes
+}
+
+fn exercise_sig(sig: &PgpSig) {
+    let _ = sig.as_bytes();
+    let _ = sig.to_bytes_new_format();
+    let _ = sig.hashed_section();
+    let _ = sig.hashed_subpackets().count();
+    let _ = sig.unhashed_subpackets().count();
+    let _ = sig.subpackets(20).count();
+    let _ = sig.timestamp();
+    let _ = sig.expiration();
+    let _ = sig.is_expired(0);
+    let _ = sig.notations();
+    let _ = sig.revocation_reason();
+    let _ = sig.signature();
+    let _ = sig.fingerprint();
+    let _ = sig.key_id();
+    let _ = sig.issuer_key_id();
+    let _ = sig.try_sig_type();
+    let _ = sig.hash_algorithm();
+    let _ = sig.check_critical_subpackets(PgpSig::KNOWN_SUBPACKETS);
+    let _ = sig.verify::<Sha256, _, _>(|hasher| hasher.input(b"data"), |_, _| true);
+    let _ = format!("{:?} {}", sig, sig);
+}
+
+#[test]
+fn mutated_signatures() {
+    let valid = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        Some(3600),
+        &[
+            SubPacket {
+                tag: Notation::TAG,
+                data: &Notation::text("a@b", "c").to_subpacket().unwrap(),
+                critical: false,
+            },
+            SubPacket {
+                tag: 29,
+                data: &[0x20, b'x'],
+                critical: true,
+            },
+        ],
+        |_| [0x11; 64],
+    );
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20_000 {
+        let bytes = mutate(&mut rng, valid.as_bytes());
+        if let Ok((sig, _)) = PgpSig::from_bytes_lenient(&bytes) {
+            exercise_sig(&sig);
+        }
+    }
+}
+
+#[test]
+fn mutated_keys() {
+    let valid = PgpKey::new::<Sha256, _>(
+        &[0x42; 32],
+        pbp_pkgx::KeyFlags::SIGN,
+        "pkgx",
+        1_700_000_000,
+        |_| [0x11; 64],
+    );
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20_000 {
+        let bytes = mutate(&mut rng, valid.as_bytes());
+        if let Ok(key) = PgpKey::from_bytes(&bytes) {
+            let _ = key.user_ids();
+            let _ = key.subkeys().len();
+            let _ = key.key_flags();
+            let _ = key.expires_at();
+            let _ = key.created_at();
+            let _ = key.fingerprint();
+            let _ = key.key_data();
+            let _ = key.preferred_hash_algorithms();
+            let _ = key.to_transferable::<Sha256, _>(|_| [0x11; 64]);
+            let _ = format!("{:?} {}", key, key);
+        }
+    }
+}
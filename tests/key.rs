@@ -0,0 +1,696 @@
+extern crate pbp_pkgx;
+
+mod common;
+
+use pbp_pkgx::{Fingerprint, KeyFlags, PgpKey, PgpSig};
+
+const KEY: &str = include_str!("../examples/props/key.txt");
+const SIG: &str = include_str!("../examples/props/sig.txt");
+
+#[test]
+fn parse_gnupg_key() {
+    let key = PgpKey::from_ascii_armor_strict(KEY).unwrap();
+    let sig = PgpSig::from_ascii_armor_strict(SIG).unwrap();
+    assert_eq!(key.fingerprint(), sig.fingerprint());
+
+    // the same key packet, with a new format header
+    let bytes = key.as_bytes();
+    let mut new_format = vec![0xc6, bytes[2]];
+    new_format.extend(&bytes[3..]);
+    assert_eq!(PgpKey::from_bytes(&new_format).unwrap(), key);
+}
+
+#[test]
+fn fingerprint_from_key_material() {
+    use pbp_pkgx::fingerprint_from_ed25519;
+
+    let public_key = [
+        0x38, 0x71, 0x06, 0x47, 0xaa, 0xfa, 0xba, 0x59, 0xa5, 0x02, 0x26, 0x9a, 0x1f, 0x87, 0xbe,
+        0x38, 0x7c, 0xe1, 0xc4, 0xba, 0xc3, 0x5f, 0xb4, 0xb9, 0x19, 0x76, 0x59, 0x1c, 0x9c, 0x03,
+        0x2f, 0x37,
+    ];
+    let fingerprint = fingerprint_from_ed25519(&public_key, 1_511_914_154);
+    assert_eq!(
+        fingerprint,
+        "B635B5FB5ED9EA73F9535F0A1CC70310BE3912D5".parse().unwrap()
+    );
+
+    let key = PgpKey::from_ascii_armor_strict(KEY).unwrap();
+    assert_eq!(key.key_data(), public_key);
+    assert_eq!(fingerprint, key.fingerprint());
+    assert_ne!(fingerprint_from_ed25519(&public_key, 0), fingerprint);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn verify_gnupg_signature() {
+    extern crate sha2;
+    use sha2::{Digest, Sha256};
+
+    let data = include_str!("../examples/props/data.txt");
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    let sig = PgpSig::from_ascii_armor(SIG).unwrap();
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data.as_bytes())));
+    assert!(!key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(b"tampered")));
+}
+
+#[test]
+fn created_at() {
+    extern crate sha2;
+
+    let key =
+        PgpKey::new::<sha2::Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 1_700_000_000, |_| {
+            [0; 64]
+        });
+    assert_eq!(key.created_at(), 1_700_000_000);
+    assert_eq!(
+        PgpKey::from_bytes(key.as_bytes()).unwrap().created_at(),
+        1_700_000_000
+    );
+
+    // 2017-11-29, when the example key was generated
+    assert_eq!(
+        PgpKey::from_ascii_armor(KEY).unwrap().created_at(),
+        1_511_914_154
+    );
+}
+
+#[test]
+fn fingerprint_formatting() {
+    let fingerprint = PgpKey::from_ascii_armor(KEY).unwrap().fingerprint();
+
+    // as printed by `gpg --fingerprint`
+    let gnupg = "B635 B5FB 5ED9 EA73 F953  5F0A 1CC7 0310 BE39 12D5";
+    assert_eq!(fingerprint.to_string(), gnupg);
+    assert_eq!(
+        format!("{:x}", fingerprint),
+        "b635b5fb5ed9ea73f9535f0a1cc70310be3912d5"
+    );
+    assert_eq!(
+        format!("{:X}", fingerprint),
+        "B635B5FB5ED9EA73F9535F0A1CC70310BE3912D5"
+    );
+
+    assert_eq!(gnupg.parse::<Fingerprint>().unwrap(), fingerprint);
+    assert_eq!(
+        "b635b5fb5ed9ea73f9535f0a1cc70310be3912d5"
+            .parse::<Fingerprint>()
+            .unwrap(),
+        fingerprint
+    );
+    assert!("b635b5fb5ed9ea73".parse::<Fingerprint>().is_err());
+    assert!("g635b5fb5ed9ea73f9535f0a1cc70310be3912d5"
+        .parse::<Fingerprint>()
+        .is_err());
+}
+
+#[test]
+fn key_flags() {
+    extern crate sha2;
+
+    let flags = KeyFlags::SIGN | KeyFlags::CERTIFY;
+    assert_eq!(PgpKey::from_ascii_armor(KEY).unwrap().key_flags(), flags);
+
+    let key = PgpKey::new::<sha2::Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, |_| [0; 64]);
+    assert!(key.key_flags().contains(KeyFlags::SIGN));
+    assert!(!key.key_flags().contains(KeyFlags::CERTIFY));
+
+    // only the key packet, without a self-signature
+    let bare = PgpKey::from_bytes(&key.as_bytes()[..54]).unwrap();
+    assert_eq!(bare.key_flags(), KeyFlags::NONE);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn dalek_key_flags() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use sha2::{Sha256, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let flags = KeyFlags::SIGN | KeyFlags::CERTIFY;
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, flags, 0, "pkgx");
+    assert_eq!(key.key_flags(), flags);
+
+    let parsed: PgpKey = key.to_string().parse().unwrap();
+    assert_eq!(parsed.key_flags(), flags);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn checked_signature() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use pbp_pkgx::SigType;
+    use sha2::{Digest, Sha256, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 1_700_000_000, "pkgx");
+    let sig = PgpSig::new_checked::<Sha256, Sha512>(
+        &keypair,
+        1_700_000_000,
+        b"data",
+        SigType::BinaryDocument,
+        1_700_000_100,
+        &[],
+    );
+    assert_eq!(sig.fingerprint(), key.fingerprint());
+    assert_eq!(sig.timestamp(), Some(1_700_000_100));
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(b"data")));
+
+    // the fingerprint covers the key's creation time
+    let sig = PgpSig::new_checked::<Sha256, Sha512>(
+        &keypair,
+        0,
+        b"data",
+        SigType::BinaryDocument,
+        1_700_000_100,
+        &[],
+    );
+    assert_ne!(sig.fingerprint(), key.fingerprint());
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn legacy_signature() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use dalek::Signer;
+    use pbp_pkgx::{KeyId, PgpError, SigType};
+    use sha2::{Digest, Sha256, Sha512};
+
+    fn write_mpi(packet: &mut Vec<u8>, mpi: &[u8]) {
+        let mpi = &mpi[mpi.iter().take_while(|&&byte| byte == 0).count()..];
+        let bits = mpi.len() * 8 - mpi[0].leading_zeros() as usize;
+        packet.extend(&[(bits >> 8) as u8, bits as u8]);
+        packet.extend(mpi);
+    }
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 1_700_000_000, "pkgx");
+    let key_id = KeyId::from_fingerprint(&key.fingerprint());
+
+    // a signature as older gpg wrote it, with only a timestamp hashed and
+    // the issuer key ID unhashed
+    let hashed = [4, 0x00, 22, 8, 0, 6, 5, 2, 0x65, 0x53, 0xf1, 0x00];
+    let mut hasher = Sha256::default();
+    hasher.input(b"data");
+    hasher.input(&hashed);
+    hasher.input(&[4, 0xff, 0, 0, 0, hashed.len() as u8]);
+    let digest = hasher.result();
+    let signature = keypair.sign(&digest).to_bytes();
+
+    let mut body = hashed.to_vec();
+    body.extend(&[0, 10, 9, 16]);
+    body.extend(&key_id.0);
+    body.extend(&digest[..2]);
+    write_mpi(&mut body, &signature[..32]);
+    write_mpi(&mut body, &signature[32..]);
+    let mut bytes = vec![0x89, 0, body.len() as u8];
+    bytes.extend(body);
+
+    match PgpSig::from_bytes(&bytes) {
+        Err(PgpError::MissingFingerprintSubpacket) => {}
+        other => panic!("expected a missing fingerprint error, got {:?}", other),
+    }
+    let sig = PgpSig::from_bytes_legacy(&bytes).unwrap();
+    assert!(!sig.has_fingerprint());
+    assert_eq!(sig.key_id(), key_id);
+    assert_eq!(sig.fingerprint()[..12], [0; 12]);
+    assert_eq!(sig.timestamp(), Some(0x6553_f100));
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(b"data")));
+    assert!(!key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(b"other")));
+
+    // without an issuer key ID, the signer cannot be identified
+    let mut unidentified = vec![0x89, 0, bytes[2] - 10];
+    unidentified.extend(&bytes[3..15]);
+    unidentified.extend(&[0, 0]);
+    unidentified.extend(&bytes[27..]);
+    match PgpSig::from_bytes_legacy(&unidentified) {
+        Err(PgpError::MissingFingerprintSubpacket) => {}
+        other => panic!("expected a missing fingerprint error, got {:?}", other),
+    }
+
+    // modern signatures parse the same way as with from_bytes
+    let sig = PgpSig::new_checked::<Sha256, Sha512>(
+        &keypair,
+        1_700_000_000,
+        b"data",
+        SigType::BinaryDocument,
+        1_700_000_100,
+        &[],
+    );
+    let legacy = PgpSig::from_bytes_legacy(sig.as_bytes()).unwrap();
+    assert!(legacy.has_fingerprint());
+    assert_eq!(legacy, sig);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn transferable_key() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use dalek::Signer;
+    use pbp_pkgx::PgpError;
+    use sha2::{Sha256, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let sign = |data: &[u8]| keypair.sign(data).to_bytes();
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 1_700_000_000, "pkgx");
+
+    // ed25519 signatures are deterministic, so this rebuilds the same key
+    let transferable = key.to_transferable::<Sha256, _>(sign).unwrap();
+    assert_eq!(transferable, key.as_bytes());
+
+    let armored = key.to_transferable_armored::<Sha256, _>(sign).unwrap();
+    assert_eq!(armored, key.to_string());
+
+    let bare = PgpKey::from_bytes(&key.as_bytes()[..54]).unwrap();
+    let err = bare.to_transferable::<Sha256, _>(sign).unwrap_err();
+    assert!(matches!(err, PgpError::MissingUserId));
+}
+
+#[test]
+fn user_ids() {
+    extern crate sha2;
+    use pbp_pkgx::PgpError;
+
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    assert_eq!(key.user_ids(), ["withoutboats <boats@mozilla.com>"]);
+
+    let key = PgpKey::new::<sha2::Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, |_| [0; 64]);
+    assert_eq!(key.user_ids(), ["pkgx"]);
+    assert_eq!(
+        PgpKey::from_bytes(key.as_bytes()).unwrap().user_ids(),
+        ["pkgx"]
+    );
+
+    // a user id packet holding invalid UTF-8
+    let mut bytes = key.as_bytes()[..54].to_vec();
+    bytes.extend(&[0xb4, 2, 0xc3, 0x28]);
+    let err = PgpKey::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::InvalidUserId));
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn revoke() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use dalek::Signer;
+    use pbp_pkgx::{RevocationReason, SigType};
+    use sha2::{Digest, Sha256, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "pkgx");
+    let sig = key.revoke::<Sha256, _>(
+        RevocationReason::KeyCompromised,
+        "leaked",
+        1_700_000_000,
+        |data| keypair.sign(data).to_bytes(),
+    );
+
+    assert_eq!(sig.sig_type(), SigType::KeyRevocation);
+    assert_eq!(sig.subpacket(29), Some(&b"\x02leaked"[..]));
+
+    // a key revocation is made over the public key packet alone
+    let key_packet = &key.as_bytes()[..54];
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(key_packet)));
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn add_revocation() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use dalek::Signer;
+    use pbp_pkgx::{PgpSig, RevocationReason, SigType};
+    use sha2::{Digest, Sha256, Sha512};
+
+    fn verify(key: &[u8; 32], data: &[u8], signature: [u8; 64]) -> bool {
+        let key = dalek::VerifyingKey::from_bytes(key).unwrap();
+        key.verify_strict(data, &dalek::Signature::from_bytes(&signature))
+            .is_ok()
+    }
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let other = dalek::SigningKey::from_bytes(&[9; 32]);
+    let mut key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "pkgx");
+
+    let data = b"release";
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &keypair,
+        data,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        0,
+    );
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+
+    // a revocation signed by another key is not valid
+    let forged = key.revoke::<Sha256, _>(RevocationReason::KeyRetired, "", 1, |data| {
+        other.sign(data).to_bytes()
+    });
+    assert!(!key.is_revoked_by::<Sha256, _>(&forged, verify));
+    assert!(!key.add_revocation::<Sha256, _>(forged, verify));
+    assert!(!key.is_revoked());
+
+    // neither is a signature of another type
+    assert!(!key.is_revoked_by::<Sha256, _>(&sig, verify));
+
+    let revocation =
+        key.revoke::<Sha256, _>(RevocationReason::KeyCompromised, "leaked", 1, |data| {
+            keypair.sign(data).to_bytes()
+        });
+    assert!(key.add_revocation::<Sha256, _>(revocation, verify));
+    assert!(key.is_revoked());
+    assert_eq!(
+        key.revocation().unwrap().revocation_reason(),
+        Some((RevocationReason::KeyCompromised, "leaked"))
+    );
+    assert!(!key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn embedded_revocation() {
+    extern crate ed25519_dalek as dalek;
+    extern crate sha2;
+    use dalek::Signer;
+    use pbp_pkgx::backend::Dalek;
+    use pbp_pkgx::{PgpSig, RevocationReason, SigType, VerifyBackend};
+    use sha2::{Digest, Sha256, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let other = dalek::SigningKey::from_bytes(&[9; 32]);
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "pkgx");
+    let data = b"release";
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &keypair,
+        data,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        0,
+    );
+
+    // a forged revocation appended to the key does not revoke it
+    let forged = key.revoke::<Sha256, _>(RevocationReason::KeyRetired, "", 1, |data| {
+        other.sign(data).to_bytes()
+    });
+    let mut bytes = key.as_bytes().to_vec();
+    bytes.extend(forged.as_bytes());
+    let mut parsed = PgpKey::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.revocation_signatures().count(), 1);
+    assert!(!parsed.add_embedded_revocation::<Sha256, _>(Dalek::verify));
+    assert!(parsed.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+
+    // a real one is only taken into account once checked
+    let revocation =
+        key.revoke::<Sha256, _>(RevocationReason::KeyCompromised, "leaked", 1, |data| {
+            keypair.sign(data).to_bytes()
+        });
+    bytes.extend(revocation.as_bytes());
+    let mut parsed = PgpKey::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed.revocation_signatures().count(), 2);
+    assert!(!parsed.is_revoked());
+    assert!(parsed.add_embedded_revocation::<Sha256, _>(Dalek::verify));
+    assert_eq!(parsed.revocation(), Some(&revocation));
+    assert!(!parsed.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+}
+
+#[test]
+fn expiration() {
+    extern crate sha2;
+    use pbp_pkgx::{HashAlgorithm, SigType};
+    use sha2::{Digest, Sha256};
+
+    use common::sign;
+    fn verify(_: &[u8; 32], hash: &[u8], signature: [u8; 64]) -> bool {
+        common::verify(hash, signature)
+    }
+
+    // created 2020-09-13, expiring a day later
+    let key = PgpKey::new_with_expiration::<Sha256, _>(
+        &[0x42; 32],
+        KeyFlags::SIGN,
+        "pkgx",
+        1_600_000_000,
+        Some(86_400),
+        sign,
+    );
+    assert_eq!(key.expires_at(), Some(1_600_086_400));
+    assert_eq!(
+        PgpKey::from_bytes(key.as_bytes()).unwrap().expires_at(),
+        Some(1_600_086_400)
+    );
+
+    let data = b"release";
+    let sig = |unix_time| {
+        PgpSig::new::<Sha256, _>(
+            data,
+            key.fingerprint(),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            unix_time,
+            None,
+            &[],
+            sign,
+        )
+    };
+    let input = |hasher: &mut Sha256| hasher.input(data);
+
+    let early = sig(1_600_000_100);
+    assert!(key.verify::<Sha256, _, _>(&early, input, verify));
+    assert!(key.verify_at::<Sha256, _, _>(&early, 1_600_000_200, input, verify));
+    assert!(!key.verify_at::<Sha256, _, _>(&early, 1_700_000_000, input, verify));
+
+    let late = sig(1_600_100_000);
+    assert!(!key.verify_at::<Sha256, _, _>(&late, 1_600_000_200, input, verify));
+
+    let forever = PgpKey::new::<Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, sign);
+    assert_eq!(forever.expires_at(), None);
+}
+
+#[test]
+fn preferences() {
+    extern crate sha2;
+    use pbp_pkgx::{HashAlgorithm, Preferences};
+    use sha2::Sha256;
+
+    let key = PgpKey::new::<Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, |_| [0; 64]);
+    assert_eq!(
+        key.preferred_hash_algorithms(),
+        [HashAlgorithm::Sha512, HashAlgorithm::Sha256]
+    );
+    assert!(key.preferred_symmetric_algorithms().is_empty());
+    assert!(key.preferred_compression_algorithms().is_empty());
+
+    // AES-256 and AES-128; ZLIB and uncompressed
+    let preferences = Preferences {
+        hash_algorithms: vec![HashAlgorithm::Sha256],
+        symmetric_algorithms: vec![9, 7],
+        compression_algorithms: vec![2, 0],
+        ..Preferences::default()
+    };
+    let key = PgpKey::new_with_preferences::<Sha256, _>(
+        &[0x42; 32],
+        KeyFlags::SIGN,
+        "pkgx",
+        0,
+        None,
+        &preferences,
+        |_| [0; 64],
+    );
+    let key = PgpKey::from_bytes(key.as_bytes()).unwrap();
+    assert_eq!(key.preferred_hash_algorithms(), [HashAlgorithm::Sha256]);
+    assert_eq!(key.preferred_symmetric_algorithms(), [9, 7]);
+    assert_eq!(key.preferred_compression_algorithms(), [2, 0]);
+
+    // a key from gpg, which states its own preferences
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    assert!(!key.preferred_hash_algorithms().is_empty());
+}
+
+#[test]
+fn key_server_preferences() {
+    extern crate sha2;
+    use pbp_pkgx::Preferences;
+    use sha2::Sha256;
+
+    let key = PgpKey::new::<Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, |_| [0; 64]);
+    assert_eq!(key.preferred_key_server(), None);
+    assert!(key.key_server_no_modify());
+
+    let preferences = Preferences {
+        key_server: Some("hkps://keys.openpgp.org".to_owned()),
+        key_server_no_modify: false,
+        ..Preferences::default()
+    };
+    let key = PgpKey::new_with_preferences::<Sha256, _>(
+        &[0x42; 32],
+        KeyFlags::SIGN,
+        "pkgx",
+        0,
+        None,
+        &preferences,
+        |_| [0; 64],
+    );
+    let key = PgpKey::from_bytes(key.as_bytes()).unwrap();
+    assert_eq!(key.preferred_key_server(), Some("hkps://keys.openpgp.org"));
+    assert!(!key.key_server_no_modify());
+
+    // a key from gpg, which sets the no-modify flag
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    assert_eq!(key.preferred_key_server(), None);
+    assert!(key.key_server_no_modify());
+}
+
+#[test]
+fn primary_user_id() {
+    extern crate sha2;
+    use sha2::Sha256;
+
+    let mut key = PgpKey::new::<Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "work", 0, |_| [0; 64]);
+    assert_eq!(key.primary_user_id(), None);
+
+    key.add_subkey::<Sha256, _, _>(
+        &[0x43; 32],
+        KeyFlags::ENCRYPT_COMS,
+        0,
+        |_| [0; 64],
+        |_| [0; 64],
+    );
+    key.add_user_id::<Sha256, _>("personal", true, 1, |_| [0; 64]);
+    key.add_user_id::<Sha256, _>("other", false, 2, |_| [0; 64]);
+    assert_eq!(key.user_ids(), ["work", "personal", "other"]);
+    assert_eq!(key.primary_user_id(), Some("personal"));
+
+    // the user ids are inserted before the subkey
+    let key = PgpKey::from_bytes(key.as_bytes()).unwrap();
+    assert_eq!(key.user_ids(), ["work", "personal", "other"]);
+    assert_eq!(key.subkeys().len(), 1);
+    assert_eq!(key.primary_user_id(), Some("personal"));
+    assert_eq!(key.key_flags(), KeyFlags::SIGN);
+
+    // a more recent primary user id takes precedence
+    let mut key = key;
+    key.add_user_id::<Sha256, _>("newest", true, 3, |_| [0; 64]);
+    assert_eq!(key.primary_user_id(), Some("newest"));
+
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    assert_eq!(key.primary_user_id(), None);
+}
+
+#[test]
+fn clone_and_order() {
+    extern crate sha2;
+    use sha2::Sha256;
+    use std::collections::BTreeMap;
+
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    let other = PgpKey::new::<Sha256, _>(&[0x42; 32], KeyFlags::SIGN, "pkgx", 0, |_| [0; 64]);
+    assert_eq!(key.clone(), key);
+
+    let mut keys = BTreeMap::new();
+    keys.insert(key.clone(), "gnupg");
+    keys.insert(other.clone(), "pkgx");
+    assert_eq!(keys[&key], "gnupg");
+    assert_eq!(keys[&other], "pkgx");
+
+    let sig = PgpSig::from_ascii_armor(SIG).unwrap();
+    let mut sigs = BTreeMap::new();
+    sigs.insert(sig.fingerprint(), sig.clone());
+    assert_eq!(sigs[&key.fingerprint()], sig);
+    assert_eq!(sig.cmp(&sig.clone()), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn key_flag_octets() {
+    for flags in [
+        KeyFlags::NONE,
+        KeyFlags::SIGN,
+        KeyFlags::CERTIFY | KeyFlags::SIGN | KeyFlags::AUTHENTICATION,
+        KeyFlags::all(),
+    ] {
+        assert_eq!(flags.to_octets(), [flags.bits()]);
+        assert_eq!(KeyFlags::from_octets(&flags.to_octets()), flags);
+    }
+
+    // the split key (0x10) and group key (0x80) flags, and any later
+    // octets, are ignored
+    assert_eq!(
+        KeyFlags::from_octets(&[0x93, 0x04, 0x08]),
+        KeyFlags::CERTIFY | KeyFlags::SIGN
+    );
+    assert_eq!(KeyFlags::from_octets(&[]), KeyFlags::NONE);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn generate_keys() {
+    extern crate rand_core;
+    extern crate sha2;
+    use rand_core::{impls, CryptoRng, RngCore};
+    use sha2::{Digest, Sha256, Sha512};
+
+    // not random, but enough to check the key comes from the generator
+    struct Counter(u8);
+
+    impl RngCore for Counter {
+        fn next_u32(&mut self) -> u32 {
+            impls::next_u32_via_fill(self)
+        }
+        fn next_u64(&mut self) -> u64 {
+            impls::next_u64_via_fill(self)
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                self.0 = self.0.wrapping_add(1);
+                *byte = self.0;
+            }
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for Counter {}
+
+    let (key, keypair) =
+        PgpKey::generate_with::<Sha256, Sha512, _>(&mut Counter(0), KeyFlags::SIGN, 0, "pkgx");
+    let seed: Vec<u8> = (1..=32).collect();
+    assert_eq!(keypair.to_bytes()[..], seed[..]);
+    assert_eq!(key.key_data(), keypair.verifying_key().to_bytes());
+    assert_eq!(key.user_ids(), ["pkgx"]);
+
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &keypair,
+        b"data",
+        key.fingerprint(),
+        pbp_pkgx::SigType::BinaryDocument,
+        0,
+    );
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(b"data")));
+
+    #[cfg(feature = "getrandom")]
+    {
+        let (first, _) = PgpKey::generate::<Sha256, Sha512>(KeyFlags::SIGN, 0, "pkgx");
+        let (second, _) = PgpKey::generate::<Sha256, Sha512>(KeyFlags::SIGN, 0, "pkgx");
+        assert_ne!(first.fingerprint(), second.fingerprint());
+    }
+}
+
+#[test]
+fn key_flags_display() {
+    assert_eq!(KeyFlags::NONE.to_string(), "none");
+    assert_eq!(KeyFlags::SIGN.to_string(), "sign");
+    assert_eq!(
+        (KeyFlags::SIGN | KeyFlags::CERTIFY).to_string(),
+        "certify, sign"
+    );
+    assert_eq!(
+        KeyFlags::all().to_string(),
+        "certify, sign, encrypt communications, encrypt storage, authenticate"
+    );
+}
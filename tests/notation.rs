@@ -0,0 +1,121 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+use pbp_pkgx::{Fingerprint, HashAlgorithm, Notation, PgpSig, SigType, SubPacket};
+use sha2::Sha256;
+
+#[test]
+fn notation_round_trip() {
+    let pipeline = Notation::text("pipeline@pkgx.dev", "ci-run-12345");
+    let binary = Notation {
+        name: "digest@pkgx.dev".to_owned(),
+        value: vec![0xff, 0x00],
+        human_readable: false,
+    };
+
+    let pipeline_data = pipeline.to_subpacket().unwrap();
+    assert_eq!(&pipeline_data[..8], &[0x80, 0, 0, 0, 0, 17, 0, 12]);
+    let binary_data = binary.to_subpacket().unwrap();
+
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[
+            SubPacket {
+                tag: Notation::TAG,
+                data: &pipeline_data,
+                critical: false,
+            },
+            SubPacket {
+                tag: Notation::TAG,
+                data: &binary_data,
+                critical: false,
+            },
+        ],
+        |_| [0; 64],
+    );
+
+    assert_eq!(sig.notations(), vec![pipeline, binary]);
+}
+
+#[test]
+fn invalid_notations() {
+    assert!(Notation::text("pipeline", "value").to_subpacket().is_err());
+    assert!(Notation::text("@pkgx.dev", "value").to_subpacket().is_err());
+    assert!(Notation::text("a@b@pkgx.dev", "value")
+        .to_subpacket()
+        .is_err());
+
+    let not_text = Notation {
+        name: "digest@pkgx.dev".to_owned(),
+        value: vec![0xff, 0x00],
+        human_readable: true,
+    };
+    assert!(not_text.to_subpacket().is_err());
+
+    assert!(Notation::from_subpacket(&[0x80, 0, 0, 0, 0, 1, 0, 5, b'a']).is_err());
+}
+
+#[test]
+fn signing_context() {
+    use sha2::Digest;
+
+    let sign = |hash: &[u8]| {
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(hash);
+        signature
+    };
+    let verify = |hash: &[u8], signature: [u8; 64]| hash == &signature[..32];
+    let new = |context, subpackets: &[SubPacket]| {
+        PgpSig::new_with_context::<Sha256, _>(
+            context,
+            b"data",
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            1_700_000_000,
+            None,
+            subpackets,
+            sign,
+        )
+    };
+    let input = |hasher: &mut Sha256| hasher.input(b"data");
+
+    let sig = PgpSig::from_bytes(new("release", &[]).as_bytes()).unwrap();
+    assert_eq!(
+        sig.notations(),
+        [Notation::text(PgpSig::CONTEXT_NOTATION, "release")]
+    );
+    assert!(sig.verify_with_context::<Sha256, _, _>("release", input, verify));
+    assert!(!sig.verify_with_context::<Sha256, _, _>("bottle", input, verify));
+    assert!(sig.verify::<Sha256, _, _>(input, verify));
+
+    // a signature without a context, or with two, is rejected
+    let plain = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert!(!plain.verify_with_context::<Sha256, _, _>("release", input, verify));
+    let second = Notation::text(PgpSig::CONTEXT_NOTATION, "release")
+        .to_subpacket()
+        .unwrap();
+    let twice = new(
+        "release",
+        &[SubPacket {
+            tag: Notation::TAG,
+            data: &second,
+            critical: false,
+        }],
+    );
+    assert!(!twice.verify_with_context::<Sha256, _, _>("release", input, verify));
+}
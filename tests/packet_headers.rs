@@ -0,0 +1,431 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpError, PgpSig, SigType, SubPacket};
+use sha2::{Digest, Sha256};
+
+fn signature(notation_len: usize) -> PgpSig {
+    PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[SubPacket {
+            tag: 20,
+            data: &vec![0; notation_len],
+            critical: false,
+        }],
+        |_| [0x11; 64],
+    )
+}
+
+#[test]
+fn new_format_headers() {
+    let sig = signature(0);
+    let body = &sig.as_bytes()[3..];
+    assert!(body.len() < 192);
+
+    let mut one_octet = vec![0xc2, body.len() as u8];
+    one_octet.extend(body);
+    assert_eq!(PgpSig::from_bytes(&one_octet).unwrap(), sig);
+
+    let long_sig = signature(100);
+    let long_body = &long_sig.as_bytes()[3..];
+    let mut two_octet = vec![0xc2, 192, (long_body.len() - 192) as u8];
+    two_octet.extend(long_body);
+    assert_eq!(PgpSig::from_bytes(&two_octet).unwrap(), long_sig);
+
+    let mut five_octet = vec![0xc2, 255, 0, 0, 0, body.len() as u8];
+    five_octet.extend(body);
+    assert_eq!(PgpSig::from_bytes(&five_octet).unwrap(), sig);
+
+    let mut partial = vec![0xc2, 224];
+    partial.extend(body);
+    match PgpSig::from_bytes(&partial) {
+        Err(PgpError::UnsupportedPartialLength) => {}
+        other => panic!("expected a partial length error, got {:?}", other),
+    }
+}
+
+#[test]
+fn new_format_round_trip() {
+    for &notation_len in &[0, 100] {
+        let sig = signature(notation_len);
+        let new_format = sig.to_bytes_new_format();
+        assert_eq!(new_format[0], 0xc2);
+        assert!(new_format.ends_with(&sig.as_bytes()[3..]));
+        assert_eq!(PgpSig::from_bytes(&new_format).unwrap(), sig);
+    }
+}
+
+#[test]
+fn exact_round_trip() {
+    let sig = signature(0);
+    let bytes = sig.as_bytes();
+    assert_eq!(PgpSig::from_bytes(bytes).unwrap().as_bytes(), bytes);
+    assert_eq!(PgpSig::from_bytes_exact(bytes).unwrap(), sig);
+
+    // the new format header is accepted, but not reproduced
+    let new_format = sig.to_bytes_new_format();
+    assert_ne!(
+        PgpSig::from_bytes(&new_format).unwrap().as_bytes(),
+        &new_format[..]
+    );
+    match PgpSig::from_bytes_exact(&new_format) {
+        Err(PgpError::NonCanonicalHeader) => {}
+        other => panic!("expected a non-canonical header error, got {:?}", other),
+    }
+}
+
+#[test]
+fn borrowed_signatures() {
+    let sig = signature(4);
+    let bytes = sig.as_bytes();
+    let borrowed = PgpSig::from_bytes_borrowed(bytes).unwrap();
+    assert_eq!(borrowed, sig.as_borrowed());
+    assert_eq!(borrowed.as_bytes().as_ptr(), bytes.as_ptr());
+    assert_eq!(borrowed.fingerprint(), sig.fingerprint());
+    assert_eq!(borrowed.key_id(), sig.key_id());
+    assert_eq!(borrowed.signature()[..], sig.signature()[..]);
+    assert_eq!(borrowed.timestamp(), Some(1_700_000_000));
+    assert_eq!(borrowed.hashed_subpackets().count(), 3);
+    assert_eq!(borrowed.to_pgp_sig(), sig);
+    assert!(borrowed.verify::<Sha256, _, _>(
+        |hasher| hasher.input(b"data"),
+        |_, signature| signature[..] == [0x11; 64][..],
+    ));
+    assert!(!borrowed.verify::<Sha256, _, _>(|hasher| hasher.input(b"other"), |_, _| true));
+
+    match PgpSig::from_bytes_borrowed(&sig.to_bytes_new_format()) {
+        Err(PgpError::NonCanonicalHeader) => {}
+        other => panic!("expected a non-canonical header error, got {:?}", other),
+    }
+    let mut trailing = bytes.to_vec();
+    trailing.push(0);
+    match PgpSig::from_bytes_borrowed(&trailing) {
+        Err(PgpError::TrailingData) => {}
+        other => panic!("expected a trailing data error, got {:?}", other),
+    }
+    match PgpSig::from_bytes_borrowed(&bytes[..bytes.len() - 1]) {
+        Err(PgpError::InvalidPacketHeader) => {}
+        other => panic!("expected a packet header error, got {:?}", other),
+    }
+}
+
+#[test]
+fn zero_fingerprint() {
+    assert!(Fingerprint::ZERO.is_zero());
+    assert!(!signature(0).fingerprint().is_zero());
+
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint::ZERO,
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |_| [0x11; 64],
+    );
+    match PgpSig::from_bytes(sig.as_bytes()) {
+        Err(PgpError::MissingFingerprintSubpacket) => {}
+        other => panic!("expected a missing fingerprint error, got {:?}", other),
+    }
+    match PgpSig::from_bytes_legacy(sig.as_bytes()) {
+        Err(PgpError::MissingFingerprintSubpacket) => {}
+        other => panic!("expected a missing fingerprint error, got {:?}", other),
+    }
+}
+
+#[test]
+fn structural_errors() {
+    let sig = signature(0);
+
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[3] = 5;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::UnexpectedSignatureVersion(5)));
+
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[5] = 1;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::UnexpectedPublicKeyAlgorithm(1)));
+    assert_eq!(err.to_string(), "Unexpected public key algorithm: 1 (RSA)");
+    assert_eq!(sig.public_key_algorithm(), 22);
+
+    bytes[5] = 100;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected public key algorithm: 100");
+
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[7] = 0xff;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::HashedSubpacketLengthOverflow));
+
+    // grow the packet by one byte, without changing its contents
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[2] += 1;
+    bytes.push(0);
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::TrailingDataAfterSignature));
+}
+
+#[test]
+fn inflated_hashed_length() {
+    let sig = signature(0);
+    let packet_len = sig.as_bytes().len() - 3;
+
+    // every hashed length which runs into or past the unhashed length is
+    // rejected when parsing, before hashed_section could read past the end
+    for hashed_len in 0..=u16::MAX {
+        let mut bytes = sig.as_bytes().to_vec();
+        bytes[7..9].copy_from_slice(&hashed_len.to_be_bytes());
+        let result = PgpSig::from_bytes(&bytes);
+        if hashed_len as usize + 8 > packet_len {
+            assert!(matches!(
+                result,
+                Err(PgpError::HashedSubpacketLengthOverflow)
+            ));
+        } else if let Ok(parsed) = result {
+            assert_eq!(parsed.hashed_section().len(), hashed_len as usize + 6);
+        }
+    }
+}
+
+#[test]
+fn trailing_data() {
+    let sig = signature(0);
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes.extend(signature(3).as_bytes());
+
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::TrailingData));
+
+    let (first, rest) = PgpSig::from_bytes_lenient(&bytes).unwrap();
+    assert_eq!(first, sig);
+    assert_eq!(first.as_bytes(), sig.as_bytes());
+    assert_eq!(PgpSig::from_bytes(rest).unwrap(), signature(3));
+}
+
+#[test]
+fn malformed_mpi() {
+    let sig = signature(0);
+    assert_eq!(sig.signature()[..], [0x11; 64][..]);
+
+    // declare the second MPI to be longer than the data present
+    let mut bytes = sig.as_bytes().to_vec();
+    let s_header = bytes.len() - 34;
+    bytes[s_header] = 0x02;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::MalformedMpi));
+}
+
+#[test]
+fn minimal_mpis() {
+    // r has a zero top byte, and s has two zero top bytes and a partial
+    // third byte
+    let mut scalars = [0x11; 64];
+    scalars[0] = 0;
+    scalars[1] = 0x80;
+    scalars[32] = 0;
+    scalars[33] = 0;
+    scalars[34] = 0x01;
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |_| scalars,
+    );
+
+    let bytes = sig.as_bytes();
+    let mpis = &bytes[(bytes.len() - 65)..];
+    assert_eq!(mpis[..3], [0x00, 0xf8, 0x80]);
+    assert_eq!(mpis[33..36], [0x00, 0xe9, 0x01]);
+    assert_eq!(mpis[33 + 2..], scalars[34..]);
+
+    let parsed = PgpSig::from_bytes(bytes).unwrap();
+    assert_eq!(parsed, sig);
+    assert_eq!(parsed.signature()[..], scalars[..]);
+
+    let (r, s) = parsed.signature_components();
+    assert_eq!(r[..], scalars[..32]);
+    assert_eq!(s[..], scalars[32..]);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn short_mpis() {
+    extern crate ed25519_dalek as dalek;
+    use dalek::Signer;
+    use sha2::{Digest, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let sign = |message: &[u8]| {
+        PgpSig::new::<Sha256, _>(
+            message,
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            1_700_000_000,
+            None,
+            &[],
+            |hash| keypair.sign(hash).to_bytes(),
+        )
+    };
+    let full_len = sign(b"message 0").as_bytes().len();
+
+    // these messages have signatures whose s and r have a zero high byte,
+    // which are encoded as 31 byte MPIs
+    for (message, zero_byte) in [(&b"message 271"[..], 32), (&b"message 523"[..], 0)] {
+        let sig = sign(message);
+        assert_eq!(sig.signature()[zero_byte], 0);
+        assert_eq!(sig.as_bytes().len(), full_len - 1);
+
+        let parsed = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+        assert_eq!(parsed.signature()[..], sig.signature()[..]);
+        let key = keypair.verifying_key();
+        assert!(parsed.verify_dalek::<Sha256, Sha512, _>(&key, |hasher| hasher.input(message)));
+    }
+}
+
+#[test]
+fn public_packet_helpers() {
+    use pbp_pkgx::packet::{
+        bigendian_u32, prepare_packet, write_mpi, write_single_subpacket, write_subpackets,
+    };
+
+    // a literal data packet: binary, named "a", dated 0x01020304
+    let literal = prepare_packet(11, |packet| {
+        packet.extend(&[b'b', 1, b'a']);
+        packet.extend(&bigendian_u32(0x0102_0304));
+        packet.extend(b"hi");
+    });
+    assert_eq!(
+        literal,
+        [0xad, 0x00, 0x09, b'b', 1, b'a', 1, 2, 3, 4, b'h', b'i']
+    );
+
+    let mut data = vec![];
+    write_subpackets(&mut data, |subpackets| {
+        write_single_subpacket(subpackets, 0x80 | 2, |subpacket| subpacket.extend(&[0; 4]));
+    });
+    assert_eq!(data, [0, 6, 5, 0x82, 0, 0, 0, 0]);
+
+    let mut mpi = vec![];
+    write_mpi(&mut mpi, &[0, 0x01, 0xff]);
+    assert_eq!(mpi, [0, 9, 0x01, 0xff]);
+}
+
+#[test]
+fn large_subpackets() {
+    use pbp_pkgx::SignError;
+
+    // subpackets with two and five octet lengths
+    for notation_len in [190, 191, 8382, 8383, 65383] {
+        let sig = signature(notation_len);
+        let parsed = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+        assert_eq!(parsed, sig);
+        let notation = parsed.hashed_subpackets().last().unwrap();
+        assert_eq!((notation.tag, notation.data.len()), (20, notation_len));
+    }
+    assert_eq!(signature(190).as_bytes()[38..40], [191, 20]);
+    assert_eq!(signature(191).as_bytes()[38..41], [192, 0, 20]);
+    assert_eq!(signature(8383).as_bytes()[38..43], [255, 0, 0, 0x20, 0xc0]);
+
+    let subpackets = |len| {
+        vec![SubPacket {
+            tag: 20,
+            data: &[0; 65536][..len],
+            critical: false,
+        }]
+    };
+    assert!(PgpSig::check_subpacket_sizes(&subpackets(65383)).is_ok());
+    let err = PgpSig::check_subpacket_sizes(&subpackets(65384)).unwrap_err();
+    assert!(matches!(err, PgpError::SubpacketTooLarge));
+
+    let result = PgpSig::try_new::<Sha256, _, ()>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &subpackets(65536),
+        |_| unreachable!(),
+    );
+    assert!(matches!(
+        result,
+        Err(SignError::Packet(PgpError::SubpacketTooLarge))
+    ));
+}
+
+#[test]
+fn read_from_reader() {
+    let short = signature(0);
+    let long = signature(300);
+    let mut stream = short.as_bytes().to_vec();
+    stream.extend(long.to_bytes_new_format());
+    stream.extend(short.as_bytes());
+
+    let mut reader = &stream[..];
+    assert_eq!(PgpSig::from_reader(&mut reader).unwrap(), short);
+    assert_eq!(PgpSig::from_reader(&mut reader).unwrap(), long);
+    assert_eq!(PgpSig::from_reader(&mut reader).unwrap(), short);
+    assert!(reader.is_empty());
+    let err = PgpSig::from_reader(&mut reader).unwrap_err();
+    assert!(matches!(err, PgpError::UnexpectedEof));
+
+    // the reader ends within the header, and within the body
+    for len in [1, 2, short.as_bytes().len() - 1] {
+        let err = PgpSig::from_reader(&mut &short.as_bytes()[..len]).unwrap_err();
+        assert!(matches!(err, PgpError::UnexpectedEof));
+    }
+
+    let err = PgpSig::from_reader(&mut &[0xc2, 224, 0][..]).unwrap_err();
+    assert!(matches!(err, PgpError::UnsupportedPartialLength));
+    let err = PgpSig::from_reader(&mut &[0x99, 0, 0][..]).unwrap_err();
+    assert!(matches!(
+        err,
+        PgpError::UnexpectedPacketType {
+            expected: 2,
+            found: 6
+        }
+    ));
+}
+
+#[test]
+fn packet_tags() {
+    use pbp_pkgx::packet::packet_tag;
+
+    let sig = signature(0);
+    assert_eq!(packet_tag(sig.as_bytes()[0]).unwrap(), 2);
+    assert_eq!(packet_tag(sig.to_bytes_new_format()[0]).unwrap(), 2);
+    assert!(matches!(
+        packet_tag(0x04),
+        Err(PgpError::InvalidPacketHeader)
+    ));
+
+    // a public key packet body with a compatible length is not a signature
+    for (mut bytes, header) in [
+        (sig.as_bytes().to_vec(), 0x99),
+        (sig.to_bytes_new_format(), 0xc6),
+    ] {
+        bytes[0] = header;
+        assert_eq!(packet_tag(header).unwrap(), 6);
+        let err = PgpSig::from_bytes(&bytes).unwrap_err();
+        assert!(matches!(
+            err,
+            PgpError::UnexpectedPacketType {
+                expected: 2,
+                found: 6
+            }
+        ));
+        assert_eq!(err.to_string(), "Expected packet type 2, found 6");
+    }
+}
@@ -0,0 +1,60 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+use pbp_pkgx::{source_date_epoch, Fingerprint, HashAlgorithm, PgpSig, SigType};
+use sha2::Sha256;
+
+#[test]
+fn reproducible_signatures() {
+    std::env::set_var("SOURCE_DATE_EPOCH", "1700000000\n");
+    let unix_time = source_date_epoch().unwrap();
+    assert_eq!(unix_time, 1_700_000_000);
+
+    let new = |unix_time| {
+        PgpSig::new::<Sha256, _>(
+            b"data",
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            unix_time,
+            None,
+            &[],
+            // a zero high byte, which must be written the same way each time
+            |hash| {
+                let mut signature = [0; 64];
+                signature[33..].copy_from_slice(&hash[1..]);
+                signature
+            },
+        )
+    };
+    assert_eq!(new(unix_time).as_bytes(), new(unix_time).as_bytes());
+    assert_eq!(new(unix_time).to_string(), new(unix_time).to_string());
+    assert_ne!(new(unix_time).as_bytes(), new(unix_time + 1).as_bytes());
+
+    std::env::set_var("SOURCE_DATE_EPOCH", "yesterday");
+    assert_eq!(source_date_epoch(), None);
+    std::env::remove_var("SOURCE_DATE_EPOCH");
+    assert_eq!(source_date_epoch(), None);
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn reproducible_dalek_signatures() {
+    extern crate ed25519_dalek as dalek;
+    use pbp_pkgx::{KeyFlags, PgpKey};
+    use sha2::Sha512;
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let sign = || {
+        let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "pkgx");
+        let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+            &keypair,
+            b"data",
+            key.fingerprint(),
+            SigType::BinaryDocument,
+            1_700_000_000,
+        );
+        (key.to_string(), sig.to_string())
+    };
+    assert_eq!(sign(), sign());
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "dalek")]
+extern crate ed25519_dalek as dalek;
+extern crate pbp_pkgx;
+extern crate rand;
+extern crate sha2;
+
+use pbp_pkgx::{backend, KeyFlags, PgpKey, PgpSig, SigType, VerifyBackend};
+use sha2::{Digest, Sha256, Sha512};
+
+const DATA: &[u8] = b"How will I ever get out of this labyrinth?";
+
+fn sign() -> (PgpKey, PgpSig) {
+    let keypair = dalek::SigningKey::from_bytes(&rand::random());
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "withoutboats");
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &keypair,
+        DATA,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        0,
+    );
+    (key, sig)
+}
+
+#[test]
+fn round_trip() {
+    let (key, sig) = sign();
+    let sig = PgpSig::from_ascii_armor(&sig.to_string()).unwrap();
+    let key = PgpKey::from_ascii_armor(&key.to_string()).unwrap();
+
+    assert!(key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(DATA)));
+    let public_key = key.to_dalek().unwrap();
+    assert!(sig.verify_dalek::<Sha256, Sha512, _>(&public_key, |hasher| hasher.input(DATA)));
+}
+
+#[test]
+fn round_trip_tampered() {
+    let (key, sig) = sign();
+    let public_key = key.to_dalek().unwrap();
+    let tampered = b"How will I ever get out of this labyrinth!";
+    assert!(!key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(tampered)));
+    assert!(!sig.verify_dalek::<Sha256, Sha512, _>(&public_key, |hasher| hasher.input(tampered)));
+
+    // a signature from another key does not verify
+    let (other, _) = sign();
+    assert!(!other.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(DATA)));
+}
+
+fn check_backend<B: VerifyBackend>() {
+    let (key, sig) = sign();
+    assert!(key.verify_with::<Sha256, B, _>(&sig, |hasher| hasher.input(DATA)));
+    assert!(sig.verify_with::<Sha256, B, _>(&key.key_data(), |hasher| hasher.input(DATA)));
+    assert!(!sig.verify_with::<Sha256, B, _>(&key.key_data(), |hasher| hasher.input(b"other")));
+
+    let (other, _) = sign();
+    assert!(!sig.verify_with::<Sha256, B, _>(&other.key_data(), |hasher| hasher.input(DATA)));
+    assert!(!sig.verify_with::<Sha256, B, _>(&[0xff; 32], |hasher| hasher.input(DATA)));
+}
+
+#[test]
+fn verify_backends() {
+    check_backend::<backend::Dalek>();
+    #[cfg(feature = "ring")]
+    check_backend::<backend::Ring>();
+    #[cfg(feature = "ed25519-compact")]
+    check_backend::<backend::Ed25519Compact>();
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn sha3_round_trip() {
+    use dalek::Signer;
+    use pbp_pkgx::sha3::Sha3_256;
+    use pbp_pkgx::HashAlgorithm;
+
+    let keypair = dalek::SigningKey::from_bytes(&rand::random());
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "withoutboats");
+    let sig = PgpSig::new::<Sha3_256, _>(
+        DATA,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha3_256,
+        0,
+        None,
+        &[],
+        |hash| keypair.sign(hash).to_bytes(),
+    );
+    let sig = PgpSig::from_ascii_armor(&sig.to_string()).unwrap();
+    assert_eq!(sig.hash_algorithm(), HashAlgorithm::Sha3_256);
+
+    assert!(key.verify_dalek::<Sha3_256, _>(&sig, |hasher| hasher.input(DATA)));
+    assert!(!key.verify_dalek::<Sha3_256, _>(&sig, |hasher| hasher.input(b"other")));
+    // SHA-256 has the same length, but not the same digest
+    assert!(!key.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(DATA)));
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "serde")]
+extern crate bincode;
+extern crate pbp_pkgx;
+extern crate serde_json;
+
+use pbp_pkgx::{PgpKey, PgpSig};
+
+const KEY: &str = include_str!("../examples/props/key.txt");
+const SIG: &str = include_str!("../examples/props/sig.txt");
+
+#[test]
+fn json_uses_ascii_armor() {
+    let sig = PgpSig::from_ascii_armor(SIG).unwrap();
+    let json = serde_json::to_string(&sig).unwrap();
+    assert_eq!(json, serde_json::to_string(&sig.to_string()).unwrap());
+    assert_eq!(serde_json::from_str::<PgpSig>(&json).unwrap(), sig);
+
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    let json = serde_json::to_string(&key).unwrap();
+    assert_eq!(serde_json::from_str::<PgpKey>(&json).unwrap(), key);
+}
+
+#[test]
+fn bincode_uses_bytes() {
+    let sig = PgpSig::from_ascii_armor(SIG).unwrap();
+    let encoded = bincode::serialize(&sig).unwrap();
+    assert!(encoded.ends_with(sig.as_bytes()));
+    assert_eq!(bincode::deserialize::<PgpSig>(&encoded).unwrap(), sig);
+
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    let encoded = bincode::serialize(&key).unwrap();
+    assert_eq!(bincode::deserialize::<PgpKey>(&encoded).unwrap(), key);
+}
+
+#[test]
+fn malformed_input_is_an_error() {
+    let err = serde_json::from_str::<PgpSig>("\"not a signature\"").unwrap_err();
+    assert!(err.to_string().contains("ASCII armor"));
+    assert!(bincode::deserialize::<PgpKey>(&[3, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3]).is_err());
+}
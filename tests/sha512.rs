@@ -0,0 +1,432 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use common::{sign, verify};
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpSig, SigType, Signature};
+use sha2::{Digest, Sha256, Sha512};
+
+const DATA: &[u8] = b"How will I ever get out of this labyrinth?";
+
+#[test]
+fn sha512_round_trip() {
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+
+    let parsed = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+    assert_eq!(parsed.hash_algorithm(), HashAlgorithm::Sha512);
+    assert!(parsed.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+    assert!(!parsed.verify::<Sha512, _, _>(|hasher| hasher.input(b"tampered"), verify));
+    assert!(!parsed.verify::<Sha256, _, _>(|hasher| hasher.input(DATA), |_, _| true));
+}
+
+#[test]
+fn sha3_algorithm_ids() {
+    use pbp_pkgx::PgpError;
+    use std::convert::TryFrom;
+
+    assert_eq!(HashAlgorithm::Sha3_256.as_byte(), 12);
+    assert_eq!(HashAlgorithm::Sha3_512.as_byte(), 14);
+    assert_eq!(
+        HashAlgorithm::try_from(14).unwrap(),
+        HashAlgorithm::Sha3_512
+    );
+    assert!(matches!(
+        HashAlgorithm::try_from(13),
+        Err(PgpError::UnsupportedHashAlgorithm(13))
+    ));
+}
+
+#[cfg(feature = "sha3")]
+#[test]
+fn sha3_digests() {
+    use pbp_pkgx::sha3::{Sha3_256, Sha3_512};
+
+    assert_eq!(
+        Sha3_256::digest(b"abc")[..],
+        [
+            0x3a, 0x98, 0x5d, 0xa7, 0x4f, 0xe2, 0x25, 0xb2, 0x04, 0x5c, 0x17, 0x2d, 0x6b, 0xd3,
+            0x90, 0xbd, 0x85, 0x5f, 0x08, 0x6e, 0x3e, 0x9d, 0x52, 0x5b, 0x46, 0xbf, 0xe2, 0x45,
+            0x11, 0x43, 0x15, 0x32,
+        ]
+    );
+
+    let sig = PgpSig::new::<Sha3_512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha3_512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(sig.as_bytes()[6], 14);
+
+    let parsed = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+    assert_eq!(parsed.hash_algorithm(), HashAlgorithm::Sha3_512);
+    assert!(parsed.verify::<Sha3_512, _, _>(|hasher| hasher.input(DATA), verify));
+    assert!(!parsed.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+}
+
+#[test]
+fn weak_hash_algorithms() {
+    use pbp_pkgx::PgpError;
+    use std::convert::TryFrom;
+
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+
+    // MD5, SHA-1 and RIPEMD-160 are parsed, but not verified by default
+    for id in 1..=3 {
+        let hash_algorithm = HashAlgorithm::try_from(id).unwrap();
+        assert!(hash_algorithm.is_weak());
+        assert!(!PgpSig::ALLOWED_HASH_ALGORITHMS.contains(&hash_algorithm));
+
+        let mut bytes = sig.as_bytes().to_vec();
+        bytes[6] = id;
+        let weak = PgpSig::from_bytes(&bytes).unwrap();
+        assert_eq!(weak.hash_algorithm(), hash_algorithm);
+        assert!(!weak.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+        let err = weak
+            .try_verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify)
+            .unwrap_err();
+        assert!(matches!(err, PgpError::WeakHashAlgorithm(byte) if byte == id));
+
+        // once allowed, the algorithm gets as far as the digest length check
+        let allowed = [hash_algorithm];
+        let mut verifier = weak.verifier::<Sha512>().allow_hash_algorithms(&allowed);
+        verifier.update(DATA);
+        let err = verifier.try_finalize(verify).unwrap_err();
+        assert!(matches!(err, PgpError::HashAlgorithmMismatch));
+    }
+    assert!(!HashAlgorithm::Sha512.is_weak());
+
+    assert!(sig.check_hash_algorithm(&[HashAlgorithm::Sha512]).is_ok());
+    let err = sig
+        .check_hash_algorithm(&[HashAlgorithm::Sha256, HashAlgorithm::Sha3_512])
+        .unwrap_err();
+    assert!(matches!(err, PgpError::DisallowedHashAlgorithm(10)));
+
+    let mut verifier = sig
+        .verifier::<Sha512>()
+        .allow_hash_algorithms(&[HashAlgorithm::Sha256]);
+    verifier.update(DATA);
+    let err = verifier.try_finalize(verify).unwrap_err();
+    assert!(matches!(err, PgpError::DisallowedHashAlgorithm(10)));
+}
+
+#[test]
+fn verify_with_fingerprint() {
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    let input = |hasher: &mut Sha512| hasher.input(DATA);
+
+    assert!(sig.verify_with_fingerprint::<Sha512, _, _>(Fingerprint([0x42; 20]), input, verify));
+    assert!(!sig.verify_with_fingerprint::<Sha512, _, _>(Fingerprint([0x43; 20]), input, verify));
+    assert!(!sig.verify_with_fingerprint::<Sha512, _, _>(
+        Fingerprint([0x42; 20]),
+        |hasher| hasher.input(b"tampered"),
+        verify
+    ));
+}
+
+#[test]
+fn from_prehashed() {
+    let sig = PgpSig::from_prehashed::<Sha512, _, _>(
+        |hasher| {
+            for chunk in DATA.chunks(7) {
+                hasher.input(chunk);
+            }
+        },
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        sign,
+    );
+    let new = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(sig, new);
+    assert!(sig.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+}
+
+#[test]
+fn fallible_signer() {
+    use pbp_pkgx::SignError;
+
+    let try_new = |sign: &dyn Fn(&[u8]) -> Result<Signature, &'static str>| {
+        PgpSig::try_new::<Sha512, _, _>(
+            DATA,
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha512,
+            1_700_000_000,
+            None,
+            &[],
+            sign,
+        )
+    };
+
+    let sig = try_new(&|hash| Ok(sign(hash))).unwrap();
+    assert!(sig.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+
+    match try_new(&|_| Err("signer unavailable")) {
+        Err(SignError::Signer("signer unavailable")) => {}
+        other => panic!("expected a signer error, got {:?}", other),
+    }
+}
+
+#[test]
+fn prepare_and_finalize() {
+    let unsigned = PgpSig::prepare::<Sha512>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+    );
+    let new = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        |hash| {
+            assert_eq!(hash, unsigned.digest());
+            sign(hash)
+        },
+    );
+
+    let signature = sign(unsigned.digest());
+    let sig = unsigned.finalize(signature);
+    assert_eq!(sig, new);
+    assert!(sig.verify::<Sha512, _, _>(|hasher| hasher.input(DATA), verify));
+}
+
+#[test]
+fn hash_prefix() {
+    use pbp_pkgx::PgpError;
+
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    let digest = sig.signature();
+    assert_eq!(sig.hash_prefix(), [digest[0], digest[1]]);
+
+    let input = |hasher: &mut Sha512| hasher.input(DATA);
+    assert!(sig.try_verify::<Sha512, _, _>(input, verify).unwrap());
+    assert!(!sig.try_verify::<Sha512, _, _>(input, |_, _| false).unwrap());
+
+    let err = sig
+        .try_verify::<Sha512, _, _>(|hasher| hasher.input(b"tampered"), |_, _| unreachable!())
+        .unwrap_err();
+    assert!(matches!(err, PgpError::HashPrefixMismatch));
+
+    let err = sig
+        .try_verify::<Sha256, _, _>(|hasher| hasher.input(DATA), |_, _| unreachable!())
+        .unwrap_err();
+    assert!(matches!(err, PgpError::HashAlgorithmMismatch));
+
+    // corrupt the stored prefix, which sits just before the two MPIs
+    let mut bytes = sig.as_bytes().to_vec();
+    let prefix = bytes.len() - 2 * (2 + 32) - 2;
+    bytes[prefix] ^= 1;
+    let corrupt = PgpSig::from_bytes(&bytes).unwrap();
+    assert_eq!(corrupt.hash_prefix()[0], digest[0] ^ 1);
+    let err = corrupt
+        .try_verify::<Sha512, _, _>(input, verify)
+        .unwrap_err();
+    assert!(matches!(err, PgpError::HashPrefixMismatch));
+    assert!(!corrupt.verify::<Sha512, _, _>(input, verify));
+}
+
+#[test]
+fn detailed_verification() {
+    use pbp_pkgx::{SubPacket, VerifyError};
+
+    let fingerprint = Fingerprint([0x42; 20]);
+    let new = |expiration, subpackets: &[SubPacket]| {
+        PgpSig::new::<Sha512, _>(
+            DATA,
+            fingerprint,
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha512,
+            1_700_000_000,
+            expiration,
+            subpackets,
+            sign,
+        )
+    };
+    let input = |hasher: &mut Sha512| hasher.input(DATA);
+    let now = 1_700_000_100;
+
+    let sig = new(Some(100), &[]);
+    assert_eq!(
+        sig.verify_detailed::<Sha512, _, _>(fingerprint, now - 1, input, verify),
+        Ok(())
+    );
+    let result = sig.verify_detailed::<Sha512, _, _>(Fingerprint([0; 20]), now - 1, input, verify);
+    assert_eq!(result, Err(VerifyError::WrongKey));
+    let result = sig.verify_detailed::<Sha512, _, _>(fingerprint, now, input, verify);
+    assert_eq!(result, Err(VerifyError::Expired));
+    let result = sig.verify_detailed::<Sha512, _, _>(fingerprint, 0, input, |_, _| false);
+    assert_eq!(result, Err(VerifyError::BadSignature));
+    let result = sig.verify_detailed::<Sha512, _, _>(
+        fingerprint,
+        0,
+        |hasher| hasher.input(b"tampered"),
+        verify,
+    );
+    assert_eq!(result, Err(VerifyError::HashPrefixMismatch));
+    let result =
+        sig.verify_detailed::<Sha256, _, _>(fingerprint, 0, |hasher| hasher.input(DATA), verify);
+    assert_eq!(result, Err(VerifyError::HashAlgorithmMismatch));
+
+    let critical = SubPacket {
+        tag: 100,
+        data: &[],
+        critical: true,
+    };
+    let sig = new(None, &[critical]);
+    assert!(sig.verify::<Sha512, _, _>(input, verify));
+    let result = sig.verify_detailed::<Sha512, _, _>(fingerprint, now, input, verify);
+    assert_eq!(result, Err(VerifyError::UnknownCriticalSubpacket(100)));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Unknown critical subpacket: 100"
+    );
+}
+
+#[test]
+fn streaming_verification() {
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+
+    let mut verifier = sig.verifier::<Sha512>();
+    for chunk in DATA.chunks(5) {
+        verifier.update(chunk);
+    }
+    assert!(verifier.finalize(verify));
+
+    let mut verifier = sig.verifier::<Sha512>();
+    verifier.update(&DATA[1..]);
+    assert!(!verifier.finalize(verify));
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn sha512_round_trip_dalek() {
+    extern crate ed25519_dalek as dalek;
+    use dalek::Signer;
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let sig = PgpSig::new::<Sha512, _>(
+        DATA,
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        |hash| keypair.sign(hash).to_bytes(),
+    );
+
+    let parsed = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+    let key = keypair.verifying_key();
+    assert!(parsed.verify_dalek::<Sha512, Sha512, _>(&key, |hasher| hasher.input(DATA)));
+    assert!(!parsed.verify_dalek::<Sha512, Sha512, _>(&key, |hasher| hasher.input(b"tampered")));
+}
+
+#[cfg(feature = "batch")]
+#[test]
+fn batch_verification() {
+    extern crate ed25519_dalek as dalek;
+    use dalek::Signer;
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let messages: Vec<&[u8]> = vec![b"first", b"second", b"third"];
+    let sigs: Vec<PgpSig> = messages
+        .iter()
+        .map(|message| {
+            PgpSig::new::<Sha256, _>(
+                message,
+                Fingerprint([0x42; 20]),
+                SigType::BinaryDocument,
+                HashAlgorithm::Sha256,
+                1_700_000_000,
+                None,
+                &[],
+                |hash| keypair.sign(hash).to_bytes(),
+            )
+        })
+        .collect();
+
+    let key = keypair.verifying_key();
+    assert_eq!(
+        PgpSig::verify_batch::<Sha256>(&sigs, &messages, &key),
+        Ok(())
+    );
+
+    let tampered: Vec<&[u8]> = vec![b"first", b"tampered", b"third"];
+    assert_eq!(
+        PgpSig::verify_batch::<Sha256>(&sigs, &tampered, &key),
+        Err(vec![1])
+    );
+    assert_eq!(
+        PgpSig::verify_batch::<Sha512>(&sigs, &messages, &key),
+        Err(vec![0, 1, 2])
+    );
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "dalek")]
+extern crate ed25519_dalek as dalek;
+extern crate pbp_pkgx;
+extern crate sha2;
+
+use dalek::Signer;
+use pbp_pkgx::{KeyFlags, PgpKey, PgpSig, SigType};
+use sha2::{Digest, Sha256, Sha512};
+
+fn verify(key: &[u8; 32], data: &[u8], signature: [u8; 64]) -> bool {
+    let key = dalek::VerifyingKey::from_bytes(key).unwrap();
+    key.verify_strict(data, &dalek::Signature::from_bytes(&signature))
+        .is_ok()
+}
+
+#[test]
+fn signing_subkey() {
+    let primary = dalek::SigningKey::from_bytes(&[7; 32]);
+    let subkey = dalek::SigningKey::from_bytes(&[8; 32]);
+
+    let mut key =
+        PgpKey::from_dalek::<Sha256, Sha512>(&primary, KeyFlags::CERTIFY, 1_700_000_000, "pkgx");
+    key.add_subkey::<Sha256, _, _>(
+        subkey.verifying_key().as_bytes(),
+        KeyFlags::SIGN,
+        1_700_000_001,
+        |data| primary.sign(data).to_bytes(),
+        |data| subkey.sign(data).to_bytes(),
+    );
+
+    let parsed: PgpKey = key.to_string().parse().unwrap();
+    assert_eq!(parsed, key);
+    assert_eq!(parsed.subkeys().len(), 1);
+
+    let sub = &parsed.subkeys()[0];
+    assert_eq!(sub.key_data(), *subkey.verifying_key().as_bytes());
+    assert_eq!(sub.created_at(), 1_700_000_001);
+    assert_eq!(sub.key_flags(), KeyFlags::SIGN);
+    assert_ne!(sub.fingerprint(), parsed.fingerprint());
+    assert!(parsed.verify_subkey_binding::<Sha256, _>(sub, verify));
+
+    // the binding signature embeds a back signature made by the subkey
+    let binding = sub.binding_signature().unwrap();
+    let back = binding.subpacket(32).unwrap();
+    let mut back_sig = vec![0x89, 0, back.len() as u8];
+    back_sig.extend(back);
+    let back_sig = PgpSig::from_bytes(&back_sig).unwrap();
+    assert_eq!(back_sig.sig_type(), SigType::PrimaryKeyBinding);
+    assert_eq!(back_sig.fingerprint(), sub.fingerprint());
+
+    let data = b"signed by the subkey";
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &subkey,
+        data,
+        sub.fingerprint(),
+        SigType::BinaryDocument,
+        1_700_000_002,
+    );
+    assert!(sub.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+    assert!(!parsed.verify_dalek::<Sha256, _>(&sig, |hasher| hasher.input(data)));
+}
+
+#[test]
+fn subkey_binding_by_another_key() {
+    let primary = dalek::SigningKey::from_bytes(&[7; 32]);
+    let other = dalek::SigningKey::from_bytes(&[9; 32]);
+    let subkey = dalek::SigningKey::from_bytes(&[8; 32]);
+
+    let mut key = PgpKey::from_dalek::<Sha256, Sha512>(&primary, KeyFlags::CERTIFY, 0, "pkgx");
+    key.add_subkey::<Sha256, _, _>(
+        subkey.verifying_key().as_bytes(),
+        KeyFlags::AUTHENTICATION,
+        0,
+        |data| other.sign(data).to_bytes(),
+        |_| unreachable!("only signing subkeys need a back signature"),
+    );
+
+    let sub = &key.subkeys()[0];
+    assert!(sub.binding_signature().unwrap().subpacket(32).is_none());
+    assert!(!key.verify_subkey_binding::<Sha256, _>(sub, verify));
+}
+
+#[test]
+fn subkey_revocation() {
+    let primary = dalek::SigningKey::from_bytes(&[7; 32]);
+    let subkey = dalek::SigningKey::from_bytes(&[8; 32]);
+
+    let mut key = PgpKey::from_dalek::<Sha256, Sha512>(&primary, KeyFlags::CERTIFY, 0, "pkgx");
+    key.add_subkey::<Sha256, _, _>(
+        subkey.verifying_key().as_bytes(),
+        KeyFlags::ENCRYPT_COMS,
+        0,
+        |data| primary.sign(data).to_bytes(),
+        |_| unreachable!(),
+    );
+    let sub = &key.subkeys()[0];
+
+    // a subkey revocation is made over the primary key and the subkey
+    let mut data = key.as_bytes()[..54].to_vec();
+    data.push(0x99);
+    data.extend(&sub.as_bytes()[1..]);
+    let revocation = PgpSig::from_dalek::<Sha256, Sha512>(
+        &primary,
+        &data,
+        key.fingerprint(),
+        SigType::SubkeyRevocation,
+        1,
+    );
+
+    assert!(key.is_subkey_revoked_by::<Sha256, _>(sub, &revocation, verify));
+    assert!(!key.is_revoked_by::<Sha256, _>(&revocation, verify));
+}
@@ -0,0 +1,412 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpError, PgpSig, SigType, SubPacket};
+use sha2::Sha256;
+
+#[test]
+fn subpacket_lookup() {
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[
+            SubPacket {
+                tag: 3,
+                data: &[0, 0, 0x0e, 0x10],
+                critical: false,
+            },
+            SubPacket {
+                tag: 27,
+                data: &[0x02],
+                critical: false,
+            },
+            SubPacket {
+                tag: 20,
+                data: b"first",
+                critical: true,
+            },
+            SubPacket {
+                tag: 20,
+                data: b"second",
+                critical: false,
+            },
+        ],
+        |_| [0; 64],
+    );
+    let sig = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+
+    assert_eq!(sig.subpacket(3), Some(&[0, 0, 0x0e, 0x10][..]));
+    assert_eq!(sig.subpacket(27), Some(&[0x02][..]));
+    assert_eq!(sig.subpacket(21), None);
+
+    let critical: Vec<(u8, bool)> = sig
+        .hashed_subpackets()
+        .map(|subpacket| (subpacket.tag, subpacket.critical))
+        .collect();
+    assert_eq!(
+        critical,
+        [
+            (33, false),
+            (2, false),
+            (3, false),
+            (27, false),
+            (20, true),
+            (20, false)
+        ]
+    );
+
+    // the critical bit is ignored on both sides of the comparison
+    let notations: Vec<&[u8]> = sig.subpackets(20).collect();
+    assert_eq!(notations, [&b"first"[..], &b"second"[..]]);
+    assert_eq!(sig.subpacket(20 | 0x80), Some(&b"first"[..]));
+
+    // the issuer key id lives in the unhashed section
+    assert_eq!(sig.subpacket(16), Some(&[0x42; 8][..]));
+}
+
+#[test]
+fn expiration() {
+    let sign = |_: &[u8]| [0; 64];
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        Some(3600),
+        &[],
+        sign,
+    );
+    let sig = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+
+    assert_eq!(sig.timestamp(), Some(1_700_000_000));
+    assert_eq!(sig.expiration(), Some(3600));
+    assert!(!sig.is_expired(1_700_003_599));
+    assert!(sig.is_expired(1_700_003_600));
+
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(sig.expiration(), None);
+    assert!(!sig.is_expired(u32::MAX));
+}
+
+#[cfg(feature = "dalek")]
+#[test]
+fn dalek_subpackets() {
+    extern crate ed25519_dalek as dalek;
+    use sha2::{Digest, Sha512};
+
+    let keypair = dalek::SigningKey::from_bytes(&[7; 32]);
+    let sig = PgpSig::from_dalek_with_subpackets::<Sha256, Sha512>(
+        &keypair,
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        1_700_000_000,
+        &[SubPacket {
+            tag: 27,
+            data: &[0x02],
+            critical: false,
+        }],
+    );
+
+    assert_eq!(sig.subpacket(27), Some(&[0x02][..]));
+    let key = keypair.verifying_key();
+    assert!(sig.verify_dalek::<Sha256, Sha512, _>(&key, |hasher| hasher.input(b"data")));
+}
+
+#[test]
+fn unknown_critical_subpackets() {
+    use sha2::Digest;
+
+    let sign = |hash: &[u8]| {
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(hash);
+        signature
+    };
+    let verify = |hash: &[u8], signature: [u8; 64]| hash == &signature[..32];
+    let sig = |tag, critical| {
+        PgpSig::new::<Sha256, _>(
+            b"data",
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            1_700_000_000,
+            None,
+            &[SubPacket {
+                tag,
+                data: &[0x01],
+                critical,
+            }],
+            sign,
+        )
+    };
+    let input = |hasher: &mut Sha256| hasher.input(b"data");
+
+    assert!(sig(27, true).verify_critical::<Sha256, _, _>(input, verify));
+    // the preferred key server and primary user id subpackets keys write
+    assert!(sig(24, true).verify_critical::<Sha256, _, _>(input, verify));
+    assert!(sig(25, true).verify_critical::<Sha256, _, _>(input, verify));
+    assert!(sig(100, false).verify_critical::<Sha256, _, _>(input, verify));
+    assert!(sig(100, true).verify::<Sha256, _, _>(input, verify));
+    assert!(!sig(100, true).verify_critical::<Sha256, _, _>(input, verify));
+
+    let err = sig(100, true).check_critical_subpackets(PgpSig::KNOWN_SUBPACKETS);
+    assert!(matches!(err, Err(PgpError::UnknownCriticalSubpacket(100))));
+    let known = [PgpSig::KNOWN_SUBPACKETS, &[100]].concat();
+    assert!(sig(100, true).check_critical_subpackets(&known).is_ok());
+    assert!(sig(100, true).verify_critical_with::<Sha256, _, _>(&known, input, verify));
+}
+
+#[test]
+fn unhashed_issuer_fingerprint() {
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |_| [0; 64],
+    );
+
+    let unhashed: Vec<(u8, &[u8])> = sig
+        .unhashed_subpackets()
+        .map(|subpacket| (subpacket.tag, subpacket.data))
+        .collect();
+    let mut issuer_fingerprint = vec![4];
+    issuer_fingerprint.extend(&[0x42; 20]);
+    assert_eq!(
+        unhashed,
+        [(16, &[0x42; 8][..]), (33, &issuer_fingerprint[..])]
+    );
+
+    // alter the last byte of the unhashed fingerprint
+    let mut bytes = sig.as_bytes().to_vec();
+    let unhashed_fingerprint = bytes
+        .windows(3)
+        .rposition(|window| window == [22, 33, 4])
+        .unwrap();
+    bytes[unhashed_fingerprint + 22] ^= 1;
+    let err = PgpSig::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::FingerprintMismatch));
+}
+
+#[test]
+fn without_unhashed() {
+    use sha2::Digest;
+
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |hash| {
+            let mut signature = [0; 64];
+            signature[..32].copy_from_slice(hash);
+            signature
+        },
+    );
+    let verify = |hash: &[u8], signature: [u8; 64]| hash == &signature[..32];
+
+    let stripped = sig.without_unhashed();
+    assert_eq!(stripped.unhashed_subpackets().count(), 0);
+    assert_eq!(stripped.issuer_key_id(), None);
+    assert_eq!(stripped.hashed_section(), sig.hashed_section());
+    assert_eq!(stripped.signature(), sig.signature());
+    assert_eq!(stripped.hash_prefix(), sig.hash_prefix());
+    assert!(stripped.verify::<Sha256, _, _>(|hasher| hasher.input(b"data"), verify));
+
+    assert_eq!(PgpSig::from_bytes(stripped.as_bytes()).unwrap(), stripped);
+    assert_eq!(stripped.without_unhashed(), stripped);
+
+    // equality is byte-exact, canonical equality ignores the unhashed area
+    assert_ne!(stripped, sig);
+    assert!(stripped.canonical_eq(&sig));
+    let parsed = PgpSig::from_bytes(&sig.to_bytes_new_format()).unwrap();
+    assert_eq!(parsed, sig);
+    assert!(parsed.canonical_eq(&sig));
+
+    let other = PgpSig::new::<Sha256, _>(
+        b"other",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |hash| {
+            let mut signature = [0; 64];
+            signature[..32].copy_from_slice(hash);
+            signature
+        },
+    );
+    assert!(!other.canonical_eq(&sig));
+}
+
+#[test]
+fn merged_unhashed() {
+    use sha2::Digest;
+
+    fn sign(data: &[u8]) -> PgpSig {
+        PgpSig::new::<Sha256, _>(
+            data,
+            Fingerprint([0x42; 20]),
+            SigType::BinaryDocument,
+            HashAlgorithm::Sha256,
+            1_700_000_000,
+            None,
+            &[],
+            |hash| {
+                let mut signature = [0; 64];
+                signature[..32].copy_from_slice(hash);
+                signature
+            },
+        )
+    }
+
+    // the same signature, with an extra unhashed notation subpacket
+    fn with_notation(sig: &PgpSig) -> PgpSig {
+        let bytes = sig.as_bytes();
+        let unhashed = 9 + sig.hashed_region().len();
+        let unhashed_len = sig.unhashed_region().len() + 6;
+        let mut body = bytes[3..unhashed].to_vec();
+        body.extend(&[(unhashed_len >> 8) as u8, unhashed_len as u8]);
+        body.extend(&[5, 20, b'n', b'o', b't', b'e']);
+        body.extend(&bytes[(unhashed + 2)..]);
+        let mut packet = vec![0x89, 0, body.len() as u8];
+        packet.extend(body);
+        PgpSig::from_bytes(&packet).unwrap()
+    }
+
+    let sig = sign(b"data");
+    let annotated = with_notation(&sig);
+    assert!(annotated.canonical_eq(&sig));
+
+    assert_eq!(sig.with_merged_unhashed(&sig).unwrap(), sig);
+    assert_eq!(
+        sig.without_unhashed().with_merged_unhashed(&sig).unwrap(),
+        sig
+    );
+    assert_eq!(annotated.with_merged_unhashed(&sig).unwrap(), annotated);
+
+    let merged = sig.with_merged_unhashed(&annotated).unwrap();
+    let tags: Vec<u8> = merged
+        .unhashed_subpackets()
+        .map(|subpacket| subpacket.tag)
+        .collect();
+    assert_eq!(tags, [16, 33, 20]);
+    assert_eq!(merged.subpacket(20), Some(&b"note"[..]));
+    assert_eq!(PgpSig::from_bytes(merged.as_bytes()).unwrap(), merged);
+    assert!(merged.verify::<Sha256, _, _>(
+        |hasher| hasher.input(b"data"),
+        |hash, signature| hash == &signature[..32]
+    ));
+
+    let err = sig.with_merged_unhashed(&sign(b"other")).unwrap_err();
+    assert!(matches!(err, PgpError::SignatureMismatch));
+}
+
+#[test]
+fn subpacket_regions() {
+    let sig = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[SubPacket {
+            tag: 23,
+            data: &[0x80],
+            critical: false,
+        }],
+        |_| [0x11; 64],
+    );
+    let bytes = sig.as_bytes();
+
+    // fingerprint (23 octets), timestamp (6) and keyserver preferences (3)
+    let hashed = sig.hashed_region();
+    assert_eq!(hashed.len(), 32);
+    assert_eq!(hashed, &bytes[9..41]);
+    assert_eq!(&bytes[7..9], [0, 32]);
+    assert_eq!(sig.hashed_section(), &bytes[3..41]);
+    assert!(sig.hashed_section().ends_with(hashed));
+    assert_eq!(sig.hashed_subpackets().count(), 3);
+
+    // issuer (10 octets) and issuer fingerprint (23)
+    let unhashed = sig.unhashed_region();
+    assert_eq!(&bytes[41..43], [0, 33]);
+    assert_eq!(unhashed, &bytes[43..76]);
+    assert_eq!(sig.unhashed_subpackets().count(), 2);
+
+    assert!(sig.without_unhashed().unhashed_region().is_empty());
+}
+
+#[test]
+fn trust_signatures() {
+    let trust = SubPacket::trust_signature_data(1, 120);
+    let regex = SubPacket::regular_expression_data("<[^>]+[@.]pkgx\\.dev>$");
+    assert_eq!(regex.last(), Some(&0));
+
+    let sig = PgpSig::new::<Sha256, _>(
+        b"key and user id",
+        Fingerprint([0x42; 20]),
+        SigType::GenericCertification,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[
+            SubPacket {
+                tag: SubPacket::TRUST_SIGNATURE,
+                data: &trust,
+                critical: true,
+            },
+            SubPacket {
+                tag: SubPacket::REGULAR_EXPRESSION,
+                data: &regex,
+                critical: true,
+            },
+        ],
+        |_| [0; 64],
+    );
+    let sig = PgpSig::from_bytes(sig.as_bytes()).unwrap();
+    assert_eq!(sig.trust_signature(), Some((1, 120)));
+    assert_eq!(sig.trust_regex(), Some("<[^>]+[@.]pkgx\\.dev>$"));
+
+    // critical trust subpackets are only accepted by callers which
+    // enforce them
+    let err = sig.check_critical_subpackets(PgpSig::KNOWN_SUBPACKETS);
+    assert!(matches!(err, Err(PgpError::UnknownCriticalSubpacket(5))));
+    let known = [PgpSig::KNOWN_SUBPACKETS, &[5, 6]].concat();
+    assert!(sig.check_critical_subpackets(&known).is_ok());
+
+    let plain = PgpSig::new::<Sha256, _>(
+        b"data",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        None,
+        &[],
+        |_| [0; 64],
+    );
+    assert_eq!(plain.trust_signature(), None);
+    assert_eq!(plain.trust_regex(), None);
+}
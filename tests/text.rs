@@ -0,0 +1,135 @@
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use common::{sign, verify};
+use pbp_pkgx::{Fingerprint, HashAlgorithm, PgpSig, SigType};
+use sha2::{Digest, Sha512};
+
+#[test]
+fn text_document_canonicalization() {
+    let sig = PgpSig::new_text::<Sha512, _>(
+        b"name: pbp  \nversion: 1\t\n",
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(sig.sig_type(), SigType::TextDocument);
+
+    assert!(sig.verify_text::<Sha512, _>(b"name: pbp  \nversion: 1\t\n", verify));
+    assert!(sig.verify_text::<Sha512, _>(b"name: pbp\r\nversion: 1\r\n", verify));
+    assert!(sig.verify_text::<Sha512, _>(b"name: pbp \rversion: 1\r", verify));
+    assert!(!sig.verify_text::<Sha512, _>(b"name: pbp\nversion: 1", verify));
+    assert!(!sig.verify_text::<Sha512, _>(b"name: pbp\n\nversion: 1\n", verify));
+
+    // the canonical form is what is actually signed
+    assert!(sig.verify::<Sha512, _, _>(
+        |hasher| hasher.input(b"name: pbp\r\nversion: 1\r\n"),
+        verify
+    ));
+}
+
+#[test]
+fn sig_type_predicates() {
+    let sig = |sig_type| {
+        PgpSig::new::<Sha512, _>(
+            b"data",
+            Fingerprint([0x42; 20]),
+            sig_type,
+            HashAlgorithm::Sha512,
+            1_700_000_000,
+            None,
+            &[],
+            sign,
+        )
+    };
+
+    let text = sig(SigType::TextDocument);
+    assert!(text.is_text() && !text.is_binary() && !text.certifies_key());
+
+    let binary = sig(SigType::BinaryDocument);
+    assert!(!binary.is_text() && binary.is_binary() && !binary.certifies_key());
+
+    for sig_type in [
+        SigType::PositiveCertification,
+        SigType::SubkeyBinding,
+        SigType::DirectlyOnKey,
+        SigType::KeyRevocation,
+    ] {
+        assert!(sig(sig_type).certifies_key());
+    }
+    assert!(!sig(SigType::Timestamp).certifies_key());
+
+    assert!(SigType::CasualCertification.is_certification());
+    assert!(!SigType::CertificationRevocation.is_certification());
+    assert!(SigType::CertificationRevocation.is_revocation());
+    assert!(!SigType::SubkeyBinding.is_revocation());
+}
+
+#[test]
+fn standalone_and_timestamp() {
+    let standalone = PgpSig::new_standalone::<Sha512, _>(
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(standalone.sig_type(), SigType::Standalone);
+    assert!(standalone.verify_standalone::<Sha512, _>(verify));
+
+    // the same as a signature of empty data
+    let empty = PgpSig::new::<Sha512, _>(
+        b"",
+        Fingerprint([0x42; 20]),
+        SigType::Standalone,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert_eq!(standalone, empty);
+
+    let timestamp = PgpSig::new_timestamp::<Sha512, _>(
+        Fingerprint([0x42; 20]),
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        &[],
+        sign,
+    );
+    assert_eq!(timestamp.sig_type(), SigType::Timestamp);
+    assert!(timestamp.verify_standalone::<Sha512, _>(verify));
+    assert!(!timestamp.verify::<Sha512, _, _>(|hasher| hasher.input(b"data"), verify));
+
+    // a binary signature of no data is not a standalone signature
+    let binary = PgpSig::new::<Sha512, _>(
+        b"",
+        Fingerprint([0x42; 20]),
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        None,
+        &[],
+        sign,
+    );
+    assert!(!binary.verify_standalone::<Sha512, _>(verify));
+}
+
+#[test]
+fn sig_type_display() {
+    assert_eq!(
+        SigType::BinaryDocument.to_string(),
+        "Binary document (0x00)"
+    );
+    assert_eq!(
+        SigType::PositiveCertification.to_string(),
+        "Positive certification (0x13)"
+    );
+    assert_eq!(SigType::DirectlyOnKey.to_string(), "Direct key (0x1f)");
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "v6")]
+extern crate pbp_pkgx;
+extern crate sha2;
+
+mod common;
+
+use common::{sign, verify};
+use pbp_pkgx::{HashAlgorithm, PgpError, PgpSig, PgpSigV6, SigType, SubPacket};
+use sha2::{Digest, Sha256, Sha512};
+
+fn signature() -> PgpSigV6 {
+    PgpSigV6::new::<Sha256, _>(
+        b"data",
+        [0x42; 32],
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        &[0x5a; 16],
+        &[],
+        sign,
+    )
+}
+
+#[test]
+fn v6_round_trip() {
+    let sig = signature();
+    let bytes = sig.as_bytes();
+    assert_eq!(bytes[3], 6);
+    assert_eq!(bytes[5], 27);
+
+    let parsed = PgpSigV6::from_bytes(bytes).unwrap();
+    assert_eq!(parsed, sig);
+    assert_eq!(parsed.try_sig_type().unwrap(), SigType::BinaryDocument);
+    assert_eq!(parsed.hash_algorithm(), HashAlgorithm::Sha256);
+    assert_eq!(parsed.fingerprint(), [0x42; 32]);
+    assert_eq!(parsed.timestamp(), Some(1_700_000_000));
+    assert_eq!(parsed.salt(), [0x5a; 16]);
+    assert_eq!(parsed.unhashed_subpackets().count(), 0);
+    assert_eq!(
+        parsed.hash_prefix(),
+        [parsed.signature()[0], parsed.signature()[1]]
+    );
+
+    // the hashed subpacket length is four octets
+    assert_eq!(bytes[7..11], [0, 0, 0, 41]);
+    assert_eq!(parsed.hashed_section().len(), 8 + 41);
+
+    assert!(parsed.verify::<Sha256, _, _>(|hasher| hasher.input(b"data"), verify));
+    assert!(!parsed.verify::<Sha256, _, _>(|hasher| hasher.input(b"other"), verify));
+    assert!(!parsed.verify::<Sha512, _, _>(|hasher| hasher.input(b"data"), |_, _| true));
+
+    // the salt is hashed before the data
+    let mut hasher = Sha256::default();
+    hasher.input(&[0x5a; 16]);
+    hasher.input(b"data");
+    hasher.input(parsed.hashed_section());
+    hasher.input(&[0x06, 0xff, 0, 0, 0, 49]);
+    assert_eq!(parsed.signature()[..32], hasher.result()[..]);
+}
+
+#[test]
+fn v6_parse_errors() {
+    let sig = signature();
+
+    let err = PgpSig::from_bytes(sig.as_bytes()).unwrap_err();
+    assert!(matches!(err, PgpError::UnexpectedSignatureVersion(6)));
+
+    // the salt length octet sits before the 16 octet salt and signature
+    let mut bytes = sig.as_bytes().to_vec();
+    let salt_len = bytes.len() - 64 - 16 - 1;
+    bytes[salt_len] = 32;
+    let err = PgpSigV6::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::InvalidSalt));
+
+    // RFC 9580 forbids SHA-1 in version 6 signatures
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[6] = 2;
+    let err = PgpSigV6::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::WeakHashAlgorithm(2)));
+
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[10] = 0xff;
+    let err = PgpSigV6::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::HashedSubpacketLengthOverflow));
+
+    let mut bytes = sig.as_bytes().to_vec();
+    bytes[2] += 1;
+    bytes.push(0);
+    let err = PgpSigV6::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, PgpError::TrailingDataAfterSignature));
+
+    // every truncation is rejected without panicking
+    let bytes = sig.as_bytes();
+    for len in 0..bytes.len() {
+        let mut truncated = bytes[..len].to_vec();
+        if len >= 3 {
+            truncated[1..3].copy_from_slice(&((len - 3) as u16).to_be_bytes());
+        }
+        assert!(PgpSigV6::from_bytes(&truncated).is_err());
+    }
+}
+
+#[test]
+fn v6_subpacket_sizes() {
+    let subpackets = |len| {
+        vec![SubPacket {
+            tag: 20,
+            data: &[0; 65536][..len],
+            critical: false,
+        }]
+    };
+    assert!(PgpSigV6::check_subpacket_sizes(&subpackets(65377)).is_ok());
+    let err = PgpSigV6::check_subpacket_sizes(&subpackets(65378)).unwrap_err();
+    assert!(matches!(err, PgpError::SubpacketTooLarge));
+
+    // the largest subpacket fits with the longest salt
+    let sig = PgpSigV6::new::<Sha512, _>(
+        b"data",
+        [0x42; 32],
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha512,
+        1_700_000_000,
+        &[0x5a; 32],
+        &subpackets(65377),
+        sign,
+    );
+    let parsed = PgpSigV6::from_bytes(sig.as_bytes()).unwrap();
+    let notation = parsed.hashed_subpackets().last().unwrap();
+    assert_eq!(notation.data.len(), 65377);
+}
+
+#[test]
+#[should_panic(expected = "subpackets are too large")]
+fn v6_subpackets_too_large() {
+    PgpSigV6::new::<Sha256, _>(
+        b"data",
+        [0x42; 32],
+        SigType::BinaryDocument,
+        HashAlgorithm::Sha256,
+        1_700_000_000,
+        &[0x5a; 16],
+        &[SubPacket {
+            tag: 20,
+            data: &[0; 65536],
+            critical: false,
+        }],
+        |_| unreachable!(),
+    );
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "wasm")]
+extern crate ed25519_dalek as dalek;
+extern crate pbp_pkgx;
+extern crate rand;
+extern crate sha2;
+
+use dalek::Signer;
+use pbp_pkgx::sha3::Sha3_256;
+use pbp_pkgx::wasm::{verify_detached, verify_detached_at};
+use pbp_pkgx::{
+    Fingerprint, HashAlgorithm, KeyFlags, PgpKey, PgpSig, RevocationReason, SigType, SubPacket,
+};
+use sha2::{Sha256, Sha512};
+
+const KEY: &str = include_str!("../examples/props/key.txt");
+const SIG: &str = include_str!("../examples/props/sig.txt");
+const DATA: &str = include_str!("../examples/props/data.txt");
+
+#[test]
+fn verify_detached_signature() {
+    let key = PgpKey::from_ascii_armor(KEY).unwrap();
+    let sig = PgpSig::from_ascii_armor(SIG).unwrap();
+    let data = DATA.as_bytes();
+
+    assert!(verify_detached(sig.as_bytes(), data, key.as_bytes()));
+    assert!(verify_detached(sig.as_bytes(), data, &key.key_data()));
+
+    assert!(!verify_detached(
+        sig.as_bytes(),
+        b"tampered",
+        key.as_bytes()
+    ));
+    assert!(!verify_detached(
+        sig.as_bytes(),
+        b"tampered",
+        &key.key_data()
+    ));
+    assert!(!verify_detached(&sig.as_bytes()[1..], data, key.as_bytes()));
+    assert!(!verify_detached(sig.as_bytes(), data, &[0; 31]));
+}
+
+#[test]
+fn verify_detached_checks() {
+    let keypair = dalek::SigningKey::from_bytes(&rand::random());
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "withoutboats");
+    let data = DATA.as_bytes();
+    let now = 1_700_000_000;
+    let sign = |fingerprint, hash_algorithm, expiration, subpackets: &[SubPacket]| {
+        let sign = |hash: &[u8]| keypair.sign(hash).to_bytes();
+        match hash_algorithm {
+            HashAlgorithm::Sha3_256 => PgpSig::new::<Sha3_256, _>(
+                data,
+                fingerprint,
+                SigType::BinaryDocument,
+                hash_algorithm,
+                now,
+                expiration,
+                subpackets,
+                sign,
+            ),
+            _ => PgpSig::new::<Sha256, _>(
+                data,
+                fingerprint,
+                SigType::BinaryDocument,
+                hash_algorithm,
+                now,
+                expiration,
+                subpackets,
+                sign,
+            ),
+        }
+    };
+
+    let sig = sign(key.fingerprint(), HashAlgorithm::Sha3_256, None, &[]);
+    assert!(verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now
+    ));
+    assert!(verify_detached_at(
+        sig.as_bytes(),
+        data,
+        &key.key_data(),
+        now
+    ));
+
+    let sig = sign(key.fingerprint(), HashAlgorithm::Sha256, Some(3600), &[]);
+    assert!(verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now + 3599
+    ));
+    assert!(!verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now + 3600
+    ));
+    assert!(!verify_detached_at(
+        sig.as_bytes(),
+        data,
+        &key.key_data(),
+        now + 3600
+    ));
+
+    let critical = [SubPacket {
+        tag: 100,
+        data: &[0],
+        critical: true,
+    }];
+    let sig = sign(key.fingerprint(), HashAlgorithm::Sha256, None, &critical);
+    assert!(!verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now
+    ));
+    assert!(!verify_detached_at(
+        sig.as_bytes(),
+        data,
+        &key.key_data(),
+        now
+    ));
+
+    // a raw key has no fingerprint to compare the issuer with
+    let sig = sign(Fingerprint([0x42; 20]), HashAlgorithm::Sha256, None, &[]);
+    assert!(!verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now
+    ));
+    assert!(verify_detached_at(
+        sig.as_bytes(),
+        data,
+        &key.key_data(),
+        now
+    ));
+}
+
+#[test]
+fn verify_detached_revoked_key() {
+    let keypair = dalek::SigningKey::from_bytes(&rand::random());
+    let other = dalek::SigningKey::from_bytes(&rand::random());
+    let key = PgpKey::from_dalek::<Sha256, Sha512>(&keypair, KeyFlags::SIGN, 0, "withoutboats");
+    let data = DATA.as_bytes();
+    let now = 1_700_000_000;
+    let sig = PgpSig::from_dalek::<Sha256, Sha512>(
+        &keypair,
+        data,
+        key.fingerprint(),
+        SigType::BinaryDocument,
+        now,
+    );
+
+    // a revocation which is not signed by the key is ignored
+    let forged = key.revoke::<Sha256, _>(RevocationReason::KeyRetired, "", now, |hash| {
+        other.sign(hash).to_bytes()
+    });
+    let mut key_bytes = key.as_bytes().to_vec();
+    key_bytes.extend(forged.as_bytes());
+    assert!(verify_detached_at(sig.as_bytes(), data, &key_bytes, now));
+
+    let revocation =
+        key.revoke::<Sha256, _>(RevocationReason::KeyCompromised, "leaked", now, |hash| {
+            keypair.sign(hash).to_bytes()
+        });
+    key_bytes.extend(revocation.as_bytes());
+    assert!(!verify_detached_at(sig.as_bytes(), data, &key_bytes, now));
+    assert!(verify_detached_at(
+        sig.as_bytes(),
+        data,
+        key.as_bytes(),
+        now
+    ));
+}